@@ -0,0 +1,99 @@
+//! Build-time check that the program ID compiled in for the selected cluster
+//! matches Anchor.toml and is not the localnet ID; a live cluster still on the
+//! placeholder builds with a warning, since it has not been deployed yet.
+
+use std::{env, fs, path::Path};
+
+const CLUSTERS: [&str; 3] = ["localnet", "devnet", "mainnet"];
+
+/// System program ID, used as the placeholder before a cluster is deployed
+const PLACEHOLDER_ID: &str = "11111111111111111111111111111111";
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=../../Anchor.toml");
+
+    let selected: Vec<&str> = CLUSTERS
+        .into_iter()
+        .filter(|cluster| env::var_os(format!("CARGO_FEATURE_{}", cluster.to_uppercase())).is_some())
+        .collect();
+    // Zero or several cluster features are reported by `compile_error!` in lib.rs
+    let [cluster] = selected[..] else {
+        return;
+    };
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+    let lib_rs = fs::read_to_string(Path::new(&manifest_dir).join("src/lib.rs"))
+        .expect("failed to read src/lib.rs");
+    // A syntax error is reported by rustc with a proper span; nothing to check here
+    let Ok(lib_rs) = syn::parse_file(&lib_rs) else {
+        return;
+    };
+    let program_id = declared_program_id(&lib_rs, cluster)
+        .unwrap_or_else(|| panic!("no `declare_id!` for the `{cluster}` feature in src/lib.rs"));
+
+    if cluster != "localnet" {
+        if program_id == PLACEHOLDER_ID {
+            println!(
+                "cargo:warning=the {cluster} program ID is still the placeholder; set the deployed ID in src/lib.rs and Anchor.toml before deploying"
+            );
+        } else {
+            assert!(
+                declared_program_id(&lib_rs, "localnet").as_ref() != Some(&program_id),
+                "the {cluster} program ID must differ from the localnet ID"
+            );
+        }
+    }
+
+    // Anchor.toml lives at the workspace root; skip the check when built outside it
+    let anchor_toml = Path::new(&manifest_dir).join("../../Anchor.toml");
+    let Ok(anchor_toml) = fs::read_to_string(anchor_toml) else {
+        return;
+    };
+    let anchor_toml: toml::Table = anchor_toml.parse().expect("Anchor.toml is not valid TOML");
+    let anchor_id = anchor_program_id(&anchor_toml, cluster)
+        .unwrap_or_else(|| panic!("no `investment_escrow` entry under [programs.{cluster}] in Anchor.toml"));
+    assert!(
+        anchor_id == program_id,
+        "{cluster} program ID mismatch: src/lib.rs declares {program_id}, Anchor.toml lists {anchor_id}"
+    );
+}
+
+/// ID in the top-level `declare_id!` gated by `#[cfg(feature = "<cluster>")]`
+fn declared_program_id(lib_rs: &syn::File, cluster: &str) -> Option<String> {
+    lib_rs.items.iter().find_map(|item| {
+        let syn::Item::Macro(item) = item else {
+            return None;
+        };
+        if !item.mac.path.is_ident("declare_id")
+            || !item.attrs.iter().any(|attr| is_feature_cfg(attr, cluster))
+        {
+            return None;
+        }
+        item.mac.parse_body::<syn::LitStr>().ok().map(|id| id.value())
+    })
+}
+
+/// Whether `attr` is `#[cfg(feature = "<cluster>")]`
+fn is_feature_cfg(attr: &syn::Attribute, cluster: &str) -> bool {
+    if !attr.path().is_ident("cfg") {
+        return false;
+    }
+    let Ok(syn::Meta::NameValue(cfg)) = attr.parse_args::<syn::Meta>() else {
+        return false;
+    };
+    cfg.path.is_ident("feature")
+        && matches!(
+            &cfg.value,
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(feature), .. }) if feature.value() == cluster
+        )
+}
+
+/// `investment_escrow = "<id>"` under `[programs.<cluster>]`
+fn anchor_program_id<'a>(anchor_toml: &'a toml::Table, cluster: &str) -> Option<&'a str> {
+    anchor_toml
+        .get("programs")?
+        .get(cluster)?
+        .get("investment_escrow")?
+        .as_str()
+}
@@ -0,0 +1,210 @@
+//! Program error codes
+
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Investment amount is below minimum")]
+    AmountTooSmall,
+
+    #[msg("Investment amount exceeds maximum")]
+    AmountTooLarge,
+
+    #[msg("Invalid return percentage (must be 0-100)")]
+    InvalidReturnPercentage,
+
+    #[msg("Insufficient funds in escrow")]
+    InsufficientFunds,
+
+    #[msg("Unauthorized access")]
+    Unauthorized,
+
+    #[msg("Arithmetic overflow")]
+    Overflow,
+
+    #[msg("Arithmetic underflow")]
+    Underflow,
+
+    #[msg("Startup name is too long")]
+    NameTooLong,
+
+    #[msg("Invalid funding target (must be non-zero and not exceed the hard cap)")]
+    InvalidFundingTarget,
+
+    #[msg("Invalid funding window (opening time must precede closing time)")]
+    InvalidFundingWindow,
+
+    #[msg("Startup is not open for investment")]
+    StartupNotOpen,
+
+    #[msg("Investment would exceed the startup's hard cap")]
+    HardCapExceeded,
+
+    #[msg("Amount exceeds the funds held in escrow for this startup")]
+    InsufficientStartupFunds,
+
+    #[msg("Startup has been cancelled")]
+    StartupCancelled,
+
+    #[msg("Investment is not active")]
+    InvestmentNotActive,
+
+    #[msg("Cooling-off period or raise has ended and the startup is not cancelled")]
+    CancellationNotAllowed,
+
+    #[msg("Minimum investment must not exceed maximum investment")]
+    InvalidInvestmentLimits,
+
+    #[msg("Platform fee must not exceed 10,000 basis points")]
+    InvalidFeeBps,
+
+    #[msg("Cooling-off period must not be negative")]
+    InvalidCoolingOffPeriod,
+
+    #[msg("Handover expiry must be positive")]
+    InvalidHandoverTtl,
+
+    #[msg("No handover is pending")]
+    NoPendingHandover,
+
+    #[msg("Handover proposal has expired")]
+    HandoverExpired,
+
+    #[msg("Invalid release approver set or threshold")]
+    InvalidApproverSet,
+
+    #[msg("Signer is not a release approver")]
+    NotReleaseApprover,
+
+    #[msg("Approver has already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Release proposal is not pending")]
+    ReleaseProposalNotPending,
+
+    #[msg("Release proposal has expired")]
+    ReleaseProposalExpired,
+
+    #[msg("Release proposal does not have enough approvals")]
+    InsufficientApprovals,
+
+    #[msg("Milestones must be non-zero and add up to at most 100% of the raise")]
+    InvalidMilestonePercent,
+
+    #[msg("Milestone is not pending review")]
+    MilestoneNotPending,
+
+    #[msg("Milestone has not been approved")]
+    MilestoneNotApproved,
+
+    #[msg("Startup raise has not reached its soft cap")]
+    RaiseNotFunded,
+
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+
+    #[msg("Startup has no active investments to distribute to")]
+    NoActiveInvestments,
+
+    #[msg("No returns available to claim")]
+    NothingToClaim,
+
+    #[msg("Investment term must be positive")]
+    InvalidTermLength,
+
+    #[msg("Investment has not reached maturity")]
+    InvestmentNotMatured,
+
+    #[msg("Startup status change not allowed")]
+    InvalidStatusTransition,
+
+    #[msg("Startup raise has not ended")]
+    RaiseNotEnded,
+
+    #[msg("Startup raise has not failed")]
+    RaiseNotFailed,
+
+    #[msg("Invalid attestation tier, jurisdiction or expiry")]
+    InvalidAttestation,
+
+    #[msg("Investor attestation has been revoked")]
+    AttestationRevoked,
+
+    #[msg("Investor attestation has expired")]
+    AttestationExpired,
+
+    #[msg("Invalid tier limits")]
+    InvalidTierLimits,
+
+    #[msg("No investment limits configured for the investor's tier")]
+    TierNotConfigured,
+
+    #[msg("Investment exceeds the investor's lifetime cap")]
+    LifetimeCapExceeded,
+
+    #[msg("Investment exceeds the investor's 12-month cap")]
+    AnnualCapExceeded,
+
+    #[msg("Operation is paused")]
+    ProgramPaused,
+
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Mint is not on the accepted-mint allowlist")]
+    MintNotAccepted,
+
+    #[msg("Mint has a Token-2022 extension that makes escrow unsafe")]
+    UnsupportedMintExtension,
+
+    #[msg("Share price must be greater than zero")]
+    InvalidSharePrice,
+
+    #[msg("Investment is too small to buy a share")]
+    AmountBelowSharePrice,
+
+    #[msg("Minimum holding period must not be negative")]
+    InvalidHoldingPeriod,
+
+    #[msg("Position is still within the minimum holding period")]
+    HoldingPeriodActive,
+
+    #[msg("Receiver must be a different wallet")]
+    InvalidReceiver,
+
+    #[msg("Invalid listing")]
+    InvalidListing,
+
+    #[msg("Listing has expired")]
+    ListingExpired,
+
+    #[msg("Listing price is above the buyer's limit")]
+    PriceExceedsLimit,
+
+    #[msg("Investment status change not allowed")]
+    InvalidInvestmentTransition,
+
+    #[msg("Account is not a v1 account of this program")]
+    InvalidMigrationTarget,
+
+    #[msg("Account is already on the current version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Investment is still active or awaiting settlement")]
+    InvestmentNotTerminal,
+
+    #[msg("Claim outstanding returns before closing")]
+    UnclaimedReturnsPending,
+
+    #[msg("No new repayments to settle this investment with")]
+    NothingToSettle,
+
+    #[msg("Mint is not a known stablecoin on this cluster")]
+    MintNotForCluster,
+
+    #[msg("Startup positions cannot be traded once it is cancelled or has failed")]
+    StartupNotTradable,
+
+    #[msg("Startup must be migrated to the current account version first")]
+    AccountNotMigrated,
+}
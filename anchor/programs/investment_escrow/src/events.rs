@@ -0,0 +1,420 @@
+//! Events emitted by the program
+
+use anchor_lang::prelude::*;
+
+use crate::state::*;
+
+/// Emitted once by `initialize_program`
+#[event]
+pub struct ProgramInitialized {
+    pub admin: Pubkey,
+    pub min_investment: u64,
+    pub max_investment: u64,
+    pub platform_fee_bps: u16,
+    pub fee_mode: FeeMode,
+    pub cooling_off_period: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a mint is added to the allowlist, enabled or disabled
+#[event]
+pub struct AcceptedMintUpdated {
+    pub mint: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when a startup is added to the on-chain catalog
+#[event]
+pub struct StartupRegistered {
+    pub startup_id: Pubkey,
+    pub name: String,
+    pub payout_wallet: Pubkey,
+    pub mint: Pubkey,
+    pub funding_target: u64,
+    pub hard_cap: u64,
+    pub opens_at: i64,
+    pub closes_at: i64,
+    pub term_length: i64,
+    pub expected_return: u8,
+    pub share_mint: Pubkey,
+    pub share_price: u64,
+}
+
+/// Emitted when the admin opens, closes or cancels a startup
+#[event]
+pub struct StartupStatusUpdated {
+    pub startup_id: Pubkey,
+    pub old_status: StartupStatus,
+    pub new_status: StartupStatus,
+    pub timestamp: i64,
+}
+
+/// Emitted when a raise is finalized as Funded or Failed
+#[event]
+pub struct RaiseFinalized {
+    pub startup_id: Pubkey,
+    pub old_status: StartupStatus,
+    pub new_status: StartupStatus,
+    pub net_raised: u64,
+    pub funding_target: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `invest_usd`; amounts are raw USDC lamports
+#[event]
+pub struct InvestmentMade {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub position_index: u64,
+    pub gross_amount: u64,
+    pub fee: u64,
+    pub principal: u64,
+    pub shares: u64,
+    pub expected_return: u8,
+    pub startup_total_raised: u64,
+    pub mint: Pubkey,
+    /// Escrow total for `mint`
+    pub total_escrow: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a release proposal is executed
+#[event]
+pub struct FundsReleased {
+    pub startup_id: Pubkey,
+    pub proposal_id: u64,
+    pub milestone_index: u8,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub startup_escrow_balance: u64,
+    pub mint: Pubkey,
+    /// Escrow total for `mint`
+    pub total_escrow: u64,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when an investment is cancelled and its principal refunded
+#[event]
+pub struct InvestmentCancelled {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub refunded: u64,
+    pub total_escrow: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a startup deposits into its repayment vault
+#[event]
+pub struct RepaymentDeposited {
+    pub startup_id: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub total_repaid: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a matured investment is settled (fully or with a shortfall)
+#[event]
+pub struct InvestmentSettled {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub settled_by: Pubkey,
+    pub amount_due: u64,
+    pub paid: u64,
+    pub settled_amount: u64,
+    pub shortfall: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when an investment in a failed raise is refunded
+#[event]
+pub struct InvestmentRefunded {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub refunded_by: Pubkey,
+    pub refunded: u64,
+    pub total_escrow: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a position is re-homed to another wallet
+#[event]
+pub struct PositionTransferred {
+    pub startup_id: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub investment_record: Pubkey,
+    pub new_investment_record: Pubkey,
+    pub principal: u64,
+    pub shares: u64,
+    /// Original investment date, carried over to the new record
+    pub investment_date: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin changes a startup's secondary-market royalty
+#[event]
+pub struct StartupRoyaltyUpdated {
+    pub startup_id: Pubkey,
+    pub old_royalty_bps: u16,
+    pub new_royalty_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Emitted when a seller lists a position's shares
+#[event]
+pub struct PositionListed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub shares: u64,
+    pub price_per_share: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a listing is withdrawn or cleared after expiry
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub investment_record: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub shares_remaining: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a buyer fills all or part of a listing
+#[event]
+pub struct ListingFilled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub new_investment_record: Pubkey,
+    pub shares: u64,
+    pub price_per_share: u64,
+    /// Total paid by the buyer, including royalty and fee
+    pub cost: u64,
+    pub royalty: u64,
+    pub fee: u64,
+    pub shares_remaining: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when returns are deposited into a startup's distribution vault
+#[event]
+pub struct ReturnsDeposited {
+    pub startup_id: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub reward_per_share: u128,
+    pub active_shares: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when an investor claims distributed returns
+#[event]
+pub struct ReturnsClaimed {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub amount: u64,
+    pub returns_claimed: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted before a terminal investment record is closed; a compact summary of the position
+#[event]
+pub struct InvestmentClosed {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub investment_record: Pubkey,
+    pub position_index: u64,
+    pub mint: Pubkey,
+    pub status: InvestmentStatus,
+    pub gross_amount: u64,
+    pub principal: u64,
+    pub returns_claimed: u64,
+    /// Paid out from the repayment vault at settlement
+    pub settled_amount: u64,
+    pub shortfall: u64,
+    pub investment_date: i64,
+    pub closed_at: i64,
+}
+
+/// Emitted when an account is upgraded in place to `ACCOUNT_VERSION`
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub migrated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when platform fees are swept from the treasury
+#[event]
+pub struct FeesWithdrawn {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `update_config` with the values before and after the change
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_min_investment: u64,
+    pub new_min_investment: u64,
+    pub old_max_investment: u64,
+    pub new_max_investment: u64,
+    pub old_platform_fee_bps: u16,
+    pub new_platform_fee_bps: u16,
+    pub old_fee_mode: FeeMode,
+    pub new_fee_mode: FeeMode,
+    pub old_cooling_off_period: i64,
+    pub new_cooling_off_period: i64,
+    pub old_handover_ttl: i64,
+    pub new_handover_ttl: i64,
+    pub old_compliance_authority: Pubkey,
+    pub new_compliance_authority: Pubkey,
+    pub old_pauser: Pubkey,
+    pub new_pauser: Pubkey,
+    pub old_min_holding_period: i64,
+    pub new_min_holding_period: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority issues or renews an attestation
+#[event]
+pub struct AttestationIssued {
+    pub investor: Pubkey,
+    pub issuer: Pubkey,
+    pub tier: u8,
+    pub jurisdiction: [u8; 2],
+    pub kyc_hash: [u8; 32],
+    pub expires_at: i64,
+    pub renewed: bool,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority revokes an attestation
+#[event]
+pub struct AttestationRevoked {
+    pub investor: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when a new admin or release authority is proposed
+#[event]
+pub struct AuthorityProposed {
+    pub role: AuthorityRole,
+    pub current: Pubkey,
+    pub proposed: Pubkey,
+    pub expires_at: i64,
+}
+
+/// Emitted when a proposed admin or release authority accepts the role
+#[event]
+pub struct AuthorityAccepted {
+    pub role: AuthorityRole,
+    pub old: Pubkey,
+    pub new: Pubkey,
+}
+
+/// Emitted when a pending handover is withdrawn
+#[event]
+pub struct AuthorityHandoverCancelled {
+    pub role: AuthorityRole,
+    pub cancelled_by: Pubkey,
+    pub proposed: Pubkey,
+}
+
+/// Emitted when the release approver set or threshold changes
+#[event]
+pub struct ReleaseApproversUpdated {
+    pub approvers: Vec<Pubkey>,
+    pub approval_threshold: u8,
+    pub release_proposal_ttl: i64,
+}
+
+/// Emitted when operations are paused or unpaused
+#[event]
+pub struct PauseUpdated {
+    pub authority: Pubkey,
+    /// True for `pause`, false for `unpause`
+    pub paused: bool,
+    /// Flags changed by this call
+    pub flags: u8,
+    /// All flags paused after this call
+    pub active_flags: u8,
+    pub timestamp: i64,
+}
+
+/// Emitted when the per-tier investment caps change
+#[event]
+pub struct TierLimitsUpdated {
+    pub tier_limits: Vec<TierLimit>,
+}
+
+/// Emitted when a release proposal is created
+#[event]
+pub struct ReleaseProposed {
+    pub startup_id: Pubkey,
+    pub proposal_id: u64,
+    pub milestone_index: u8,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub proposer: Pubkey,
+    pub expires_at: i64,
+}
+
+/// Emitted when an approver approves a release proposal
+#[event]
+pub struct ReleaseApproved {
+    pub startup_id: Pubkey,
+    pub proposal_id: u64,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub approval_threshold: u8,
+}
+
+/// Emitted when a release proposal is cancelled
+#[event]
+pub struct ReleaseCancelled {
+    pub startup_id: Pubkey,
+    pub proposal_id: u64,
+    pub cancelled_by: Pubkey,
+}
+
+/// Emitted when a milestone is created
+#[event]
+pub struct MilestoneCreated {
+    pub startup_id: Pubkey,
+    pub index: u8,
+    pub description_hash: [u8; 32],
+    pub percent_bps: u16,
+    pub due_date: i64,
+}
+
+/// Emitted when the release authority approves or rejects a milestone
+#[event]
+pub struct MilestoneReviewed {
+    pub startup_id: Pubkey,
+    pub index: u8,
+    pub state: MilestoneState,
+    pub reviewed_by: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,494 @@
+//! Program setup and admin-gated configuration
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::cluster_mints;
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::handover::propose_release_authority_handover;
+
+/// Initialize the program (admin only)
+#[derive(Accounts)]
+#[instruction(admin: Pubkey)]
+pub struct InitializeProgram<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ProgramConfig::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = EscrowState::LEN,
+        seeds = [b"escrow"],
+        bump
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Add a stablecoin to the accepted-mint allowlist and create its escrow vault (admin only)
+#[derive(Accounts)]
+pub struct AddAcceptedMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AcceptedMint::LEN,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Escrow vault for this mint
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_pda,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Enable or disable an accepted mint (admin only)
+#[derive(Accounts)]
+pub struct SetAcceptedMintEnabled<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+}
+
+/// Update program configuration (admin only)
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Set the release approver set and threshold (admin only)
+#[derive(Accounts)]
+pub struct SetReleaseApprovers<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+/// Pause or unpause operations (pauser or admin)
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+/// Set the per-tier investment caps (admin only)
+#[derive(Accounts)]
+pub struct SetTierLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+/// Sweep platform fees out of the treasury (admin only)
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = fee_pda
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub destination_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"fee_authority"],
+        bump
+    )]
+    pub fee_pda: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn initialize_program(
+    ctx: Context<InitializeProgram>,
+    admin: Pubkey,
+    min_investment: u64,
+    max_investment: u64,
+    platform_fee_bps: u16,
+    fee_mode: FeeMode,
+    cooling_off_period: i64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin = admin;
+    config.min_investment = min_investment;
+    config.max_investment = max_investment;
+    config.platform_fee_bps = platform_fee_bps;
+    config.fee_mode = fee_mode;
+    config.cooling_off_period = cooling_off_period;
+    config.handover_ttl = ProgramConfig::DEFAULT_HANDOVER_TTL;
+    config.pending_admin = None;
+    config.pending_admin_expires_at = 0;
+    config.release_approvers = vec![admin];
+    config.approval_threshold = 1;
+    config.release_proposal_ttl = ProgramConfig::DEFAULT_RELEASE_PROPOSAL_TTL;
+    config.compliance_authority = admin;
+    config.tier_limits = Vec::new();
+    config.pauser = admin;
+    config.paused = 0;
+    config.min_holding_period = 0;
+    config.bump = ctx.bumps.config;
+    config.version = ACCOUNT_VERSION;
+    config.validate()?;
+
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.active_investments = 0;
+    escrow_state.release_authority = admin;
+    escrow_state.pending_release_authority = None;
+    escrow_state.pending_release_authority_expires_at = 0;
+    escrow_state.bump = ctx.bumps.escrow_state;
+    escrow_state.version = ACCOUNT_VERSION;
+
+    emit!(ProgramInitialized {
+        admin,
+        min_investment,
+        max_investment,
+        platform_fee_bps,
+        fee_mode,
+        cooling_off_period,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn add_accepted_mint(ctx: Context<AddAcceptedMint>) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        cluster_mints::ALL.contains(&ctx.accounts.mint.key()),
+        ErrorCode::MintNotForCluster
+    );
+    AcceptedMint::check_extensions(&ctx.accounts.mint.to_account_info())?;
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.mint = ctx.accounts.mint.key();
+    accepted_mint.enabled = true;
+    accepted_mint.total_escrow = 0;
+    accepted_mint.total_fees_collected = 0;
+    accepted_mint.bump = ctx.bumps.accepted_mint;
+    accepted_mint.version = ACCOUNT_VERSION;
+
+    emit!(AcceptedMintUpdated {
+        mint: accepted_mint.mint,
+        enabled: true,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_accepted_mint_enabled(
+    ctx: Context<SetAcceptedMintEnabled>,
+    enabled: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.enabled = enabled;
+
+    emit!(AcceptedMintUpdated {
+        mint: accepted_mint.mint,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_config(
+    ctx: Context<UpdateConfig>,
+    min_investment: Option<u64>,
+    max_investment: Option<u64>,
+    platform_fee_bps: Option<u16>,
+    fee_mode: Option<FeeMode>,
+    cooling_off_period: Option<i64>,
+    handover_ttl: Option<i64>,
+    release_authority: Option<Pubkey>,
+    compliance_authority: Option<Pubkey>,
+    pauser: Option<Pubkey>,
+    min_holding_period: Option<i64>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    let old_min_investment = config.min_investment;
+    let old_max_investment = config.max_investment;
+    let old_platform_fee_bps = config.platform_fee_bps;
+    let old_fee_mode = config.fee_mode;
+    let old_cooling_off_period = config.cooling_off_period;
+    let old_handover_ttl = config.handover_ttl;
+    let old_compliance_authority = config.compliance_authority;
+    let old_pauser = config.pauser;
+    let old_min_holding_period = config.min_holding_period;
+
+    if let Some(min_investment) = min_investment {
+        config.min_investment = min_investment;
+    }
+    if let Some(max_investment) = max_investment {
+        config.max_investment = max_investment;
+    }
+    if let Some(platform_fee_bps) = platform_fee_bps {
+        config.platform_fee_bps = platform_fee_bps;
+    }
+    if let Some(fee_mode) = fee_mode {
+        config.fee_mode = fee_mode;
+    }
+    if let Some(cooling_off_period) = cooling_off_period {
+        config.cooling_off_period = cooling_off_period;
+    }
+    if let Some(handover_ttl) = handover_ttl {
+        config.handover_ttl = handover_ttl;
+    }
+    if let Some(compliance_authority) = compliance_authority {
+        config.compliance_authority = compliance_authority;
+    }
+    if let Some(pauser) = pauser {
+        config.pauser = pauser;
+    }
+    if let Some(min_holding_period) = min_holding_period {
+        config.min_holding_period = min_holding_period;
+    }
+    config.validate()?;
+
+    // Proposed after any `handover_ttl` change, so the new expiry applies
+    if let Some(release_authority) = release_authority {
+        propose_release_authority_handover(
+            &mut ctx.accounts.escrow_state,
+            config.handover_ttl,
+            release_authority,
+        )?;
+    }
+
+    emit!(ConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_min_investment,
+        new_min_investment: config.min_investment,
+        old_max_investment,
+        new_max_investment: config.max_investment,
+        old_platform_fee_bps,
+        new_platform_fee_bps: config.platform_fee_bps,
+        old_fee_mode,
+        new_fee_mode: config.fee_mode,
+        old_cooling_off_period,
+        new_cooling_off_period: config.cooling_off_period,
+        old_handover_ttl,
+        new_handover_ttl: config.handover_ttl,
+        old_compliance_authority,
+        new_compliance_authority: config.compliance_authority,
+        old_pauser,
+        new_pauser: config.pauser,
+        old_min_holding_period,
+        new_min_holding_period: config.min_holding_period,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn pause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == config.pauser || authority == config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        flags != 0 && flags & !ProgramConfig::PAUSE_ALL == 0,
+        ErrorCode::InvalidPauseFlags
+    );
+
+    config.paused |= flags;
+
+    emit!(PauseUpdated {
+        authority,
+        paused: true,
+        flags,
+        active_flags: config.paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn unpause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let authority = ctx.accounts.authority.key();
+    require!(authority == config.admin, ErrorCode::Unauthorized);
+    require!(
+        flags != 0 && flags & !ProgramConfig::PAUSE_ALL == 0,
+        ErrorCode::InvalidPauseFlags
+    );
+
+    config.paused &= !flags;
+
+    emit!(PauseUpdated {
+        authority,
+        paused: false,
+        flags,
+        active_flags: config.paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_release_approvers(
+    ctx: Context<SetReleaseApprovers>,
+    approvers: Vec<Pubkey>,
+    approval_threshold: u8,
+    release_proposal_ttl: i64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+    require!(
+        approvers.len() <= ProgramConfig::MAX_RELEASE_APPROVERS,
+        ErrorCode::InvalidApproverSet
+    );
+
+    config.release_approvers = approvers;
+    config.approval_threshold = approval_threshold;
+    config.release_proposal_ttl = release_proposal_ttl;
+    config.validate()?;
+
+    emit!(ReleaseApproversUpdated {
+        approvers: config.release_approvers.clone(),
+        approval_threshold,
+        release_proposal_ttl,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_tier_limits(ctx: Context<SetTierLimits>, tier_limits: Vec<TierLimit>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(ctx.accounts.admin.key() == config.admin, ErrorCode::Unauthorized);
+    require!(
+        tier_limits.len() <= ProgramConfig::MAX_TIERS,
+        ErrorCode::InvalidTierLimits
+    );
+
+    config.tier_limits = tier_limits;
+    config.validate()?;
+
+    emit!(TierLimitsUpdated {
+        tier_limits: config.tier_limits.clone(),
+    });
+
+    Ok(())
+}
+
+pub(crate) fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+
+    require!(
+        ctx.accounts.treasury_ata.amount >= amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    let fee_bump = ctx.bumps.fee_pda;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"fee_authority", &[fee_bump]]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.treasury_ata.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.destination_ata.to_account_info(),
+        authority: ctx.accounts.fee_pda.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    emit!(FeesWithdrawn {
+        admin: ctx.accounts.admin.key(),
+        destination: ctx.accounts.destination_ata.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
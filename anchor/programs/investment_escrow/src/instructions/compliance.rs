@@ -0,0 +1,132 @@
+//! Investor attestations issued by the compliance authority
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+/// Issue an investor attestation (compliance authority only)
+#[derive(Accounts)]
+#[instruction(investor: Pubkey)]
+pub struct IssueAttestation<'info> {
+    #[account(mut)]
+    pub compliance_authority: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = compliance_authority,
+        space = InvestorAttestation::LEN,
+        seeds = [b"attestation", investor.as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, InvestorAttestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Renew or revoke an investor attestation (compliance authority only)
+#[derive(Accounts)]
+#[instruction(investor: Pubkey)]
+pub struct UpdateAttestation<'info> {
+    pub compliance_authority: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"attestation", investor.as_ref()],
+        bump = attestation.bump
+    )]
+    pub attestation: Account<'info, InvestorAttestation>,
+}
+
+pub(crate) fn issue_attestation(
+    ctx: Context<IssueAttestation>,
+    investor: Pubkey,
+    tier: u8,
+    jurisdiction: [u8; 2],
+    kyc_hash: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let issuer = ctx.accounts.compliance_authority.key();
+    require!(
+        issuer == ctx.accounts.config.compliance_authority,
+        ErrorCode::Unauthorized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let attestation = &mut ctx.accounts.attestation;
+    attestation.investor = investor;
+    attestation.bump = ctx.bumps.attestation;
+    attestation.version = ACCOUNT_VERSION;
+    attestation.record(issuer, tier, jurisdiction, kyc_hash, expires_at, now)?;
+
+    emit!(AttestationIssued {
+        investor,
+        issuer,
+        tier,
+        jurisdiction,
+        kyc_hash,
+        expires_at,
+        renewed: false,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn renew_attestation(
+    ctx: Context<UpdateAttestation>,
+    investor: Pubkey,
+    tier: u8,
+    jurisdiction: [u8; 2],
+    kyc_hash: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let issuer = ctx.accounts.compliance_authority.key();
+    require!(
+        issuer == ctx.accounts.config.compliance_authority,
+        ErrorCode::Unauthorized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .attestation
+        .record(issuer, tier, jurisdiction, kyc_hash, expires_at, now)?;
+
+    emit!(AttestationIssued {
+        investor,
+        issuer,
+        tier,
+        jurisdiction,
+        kyc_hash,
+        expires_at,
+        renewed: true,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn revoke_attestation(ctx: Context<UpdateAttestation>, investor: Pubkey) -> Result<()> {
+    let issuer = ctx.accounts.compliance_authority.key();
+    require!(
+        issuer == ctx.accounts.config.compliance_authority,
+        ErrorCode::Unauthorized
+    );
+
+    ctx.accounts.attestation.revoked = true;
+
+    emit!(AttestationRevoked {
+        investor,
+        revoked_by: issuer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
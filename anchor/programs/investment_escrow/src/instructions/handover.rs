@@ -0,0 +1,174 @@
+//! Two-step handover of the admin and release authority roles
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+/// Propose, accept or cancel an admin/release-authority handover
+#[derive(Accounts)]
+pub struct HandoverAuthority<'info> {
+    /// Current admin/release authority (propose, cancel) or proposed key (accept)
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+pub(crate) fn propose_admin(ctx: Context<HandoverAuthority>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(ctx.accounts.authority.key() == config.admin, ErrorCode::Unauthorized);
+
+    let expires_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(config.handover_ttl)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    config.pending_admin = Some(new_admin);
+    config.pending_admin_expires_at = expires_at;
+
+    emit!(AuthorityProposed {
+        role: AuthorityRole::Admin,
+        current: config.admin,
+        proposed: new_admin,
+        expires_at,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn accept_admin(ctx: Context<HandoverAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let new_admin = config.pending_admin.ok_or(error!(ErrorCode::NoPendingHandover))?;
+    require!(ctx.accounts.authority.key() == new_admin, ErrorCode::Unauthorized);
+    require!(
+        Clock::get()?.unix_timestamp <= config.pending_admin_expires_at,
+        ErrorCode::HandoverExpired
+    );
+
+    let old_admin = config.admin;
+    config.admin = new_admin;
+    config.pending_admin = None;
+    config.pending_admin_expires_at = 0;
+
+    emit!(AuthorityAccepted {
+        role: AuthorityRole::Admin,
+        old: old_admin,
+        new: new_admin,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_admin_handover(ctx: Context<HandoverAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(ctx.accounts.authority.key() == config.admin, ErrorCode::Unauthorized);
+    let proposed = config.pending_admin.ok_or(error!(ErrorCode::NoPendingHandover))?;
+
+    config.pending_admin = None;
+    config.pending_admin_expires_at = 0;
+
+    emit!(AuthorityHandoverCancelled {
+        role: AuthorityRole::Admin,
+        cancelled_by: ctx.accounts.authority.key(),
+        proposed,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn propose_release_authority(
+    ctx: Context<HandoverAuthority>,
+    new_release_authority: Pubkey,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.config.admin
+            || authority == ctx.accounts.escrow_state.release_authority,
+        ErrorCode::Unauthorized
+    );
+
+    propose_release_authority_handover(
+        &mut ctx.accounts.escrow_state,
+        ctx.accounts.config.handover_ttl,
+        new_release_authority,
+    )
+}
+
+pub(crate) fn accept_release_authority(ctx: Context<HandoverAuthority>) -> Result<()> {
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    let new_release_authority = escrow_state
+        .pending_release_authority
+        .ok_or(error!(ErrorCode::NoPendingHandover))?;
+    require!(
+        ctx.accounts.authority.key() == new_release_authority,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        Clock::get()?.unix_timestamp <= escrow_state.pending_release_authority_expires_at,
+        ErrorCode::HandoverExpired
+    );
+
+    let old_release_authority = escrow_state.release_authority;
+    escrow_state.release_authority = new_release_authority;
+    escrow_state.pending_release_authority = None;
+    escrow_state.pending_release_authority_expires_at = 0;
+
+    emit!(AuthorityAccepted {
+        role: AuthorityRole::ReleaseAuthority,
+        old: old_release_authority,
+        new: new_release_authority,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_release_authority_handover(ctx: Context<HandoverAuthority>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    require!(
+        authority == ctx.accounts.config.admin || authority == escrow_state.release_authority,
+        ErrorCode::Unauthorized
+    );
+    let proposed = escrow_state
+        .pending_release_authority
+        .ok_or(error!(ErrorCode::NoPendingHandover))?;
+
+    escrow_state.pending_release_authority = None;
+    escrow_state.pending_release_authority_expires_at = 0;
+
+    emit!(AuthorityHandoverCancelled {
+        role: AuthorityRole::ReleaseAuthority,
+        cancelled_by: authority,
+        proposed,
+    });
+
+    Ok(())
+}
+
+/// Move a pending milestone to `state` on behalf of the release authority
+/// Record `new_release_authority` as the pending release authority, valid for `handover_ttl`
+pub(crate) fn propose_release_authority_handover(
+    escrow_state: &mut EscrowState,
+    handover_ttl: i64,
+    new_release_authority: Pubkey,
+) -> Result<()> {
+    let expires_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(handover_ttl)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    escrow_state.pending_release_authority = Some(new_release_authority);
+    escrow_state.pending_release_authority_expires_at = expires_at;
+
+    emit!(AuthorityProposed {
+        role: AuthorityRole::ReleaseAuthority,
+        current: escrow_state.release_authority,
+        proposed: new_release_authority,
+        expires_at,
+    });
+
+    Ok(())
+}
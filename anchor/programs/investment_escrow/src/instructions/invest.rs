@@ -0,0 +1,559 @@
+//! Investing, cancelling and refunding
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::{burn_shares, mint_shares};
+
+/// Investment context - Core feature for USDC transfers
+#[derive(Accounts)]
+#[instruction(amount: u64, startup_id: Pubkey)]
+pub struct InvestUSD<'info> {
+    /// The investor who signs and authorizes the transaction
+    #[account(mut)]
+    pub investor: Signer<'info>,
+
+    /// Investor's USDC Token Account (source of funds)
+    /// Must have sufficient balance for the investment
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = investor
+    )]
+    pub investor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Program's Escrow Token Account (destination)
+    /// The escrow PDA's vault for this mint, pooled across all investors
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_pda,
+        associated_token::token_program = token_program
+    )]
+    pub program_escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow PDA authority (seeds: ["escrow_authority"])
+    /// Derived PDA that controls the escrow account
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    /// Fee Treasury Token Account (receives platform fees)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = fee_pda
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fee PDA authority (seeds: ["fee_authority"])
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"fee_authority"],
+        bump
+    )]
+    pub fee_pda: UncheckedAccount<'info>,
+
+    /// Investor profile PDA - Created on first investment, holds the position counter
+    #[account(
+        init_if_needed,
+        payer = investor,
+        space = InvestorProfile::LEN,
+        seeds = [b"investor", investor.key().as_ref()],
+        bump
+    )]
+    pub investor_profile: Account<'info, InvestorProfile>,
+
+    /// Investor's KYC attestation - must be unrevoked and unexpired
+    #[account(
+        seeds = [b"attestation", investor.key().as_ref()],
+        bump = attestation.bump
+    )]
+    pub attestation: Account<'info, InvestorAttestation>,
+
+    /// Investment record PDA - Created here, stores immutable investment data
+    /// Unique per investor per position (derived from the profile's counter)
+    #[account(
+        init,
+        payer = investor,
+        space = InvestmentAccount::LEN,
+        seeds = [
+            b"investment",
+            investor.key().as_ref(),
+            &investor_profile.investment_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    /// Registered startup receiving the investment
+    /// Must exist in the on-chain catalog (verified by seeds)
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    /// Allowlist entry holding the per-mint escrow totals
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", startup.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Program configuration account
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Escrow state account
+    #[account(mut, seeds = [b"escrow"], bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// Mint the startup raises in (SPL Token standard)
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Startup's share mint (minted to the investor)
+    #[account(mut, address = startup.share_mint)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Investor's share account - Created on the first investment in this startup
+    #[account(
+        init_if_needed,
+        payer = investor,
+        associated_token::mint = share_mint,
+        associated_token::authority = investor,
+        associated_token::token_program = share_token_program
+    )]
+    pub investor_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// SPL Token Program (for CPI calls)
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub share_token_program: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System Program (for account creation)
+    pub system_program: Program<'info, System>,
+
+    /// Clock Sysvar (for timestamps)
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Cancel an investment and refund its principal
+/// Shared by `cancel_investment` (investor or admin) and `refund_investment` (anyone)
+#[derive(Accounts)]
+pub struct CancelInvestment<'info> {
+    /// Investor (inside the cooling-off window), admin (cancelled startup),
+    /// or any signer (failed raise)
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", investment_record.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    /// Allowlist entry holding the per-mint escrow totals
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", startup.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// Investor's USDC Token Account (receives the refund)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = investment_record.investor
+    )]
+    pub investor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_pda,
+        associated_token::token_program = token_program
+    )]
+    pub program_escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = startup.share_mint)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Position holder's share account (shares are burned on exit)
+    #[account(
+        mut,
+        associated_token::mint = share_mint,
+        associated_token::authority = investment_record.investor,
+        associated_token::token_program = share_token_program
+    )]
+    pub investor_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub share_token_program: Program<'info, Token2022>,
+}
+
+pub(crate) fn invest_usd(
+    ctx: Context<InvestUSD>,
+    amount: u64,
+    startup_id: Pubkey,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+
+    // ===== VALIDATION =====
+    config.require_not_paused(ProgramConfig::PAUSE_DEPOSITS)?;
+    require!(ctx.accounts.accepted_mint.enabled, ErrorCode::MintNotAccepted);
+    require!(amount >= config.min_investment, ErrorCode::AmountTooSmall);
+    require!(amount <= config.max_investment, ErrorCode::AmountTooLarge);
+
+    let fee = match config.fee_mode {
+        FeeMode::OnInvest => config.platform_fee(amount)?,
+        FeeMode::OnRelease => 0,
+    };
+    let escrow_amount = amount
+        .checked_sub(fee)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    let startup = &ctx.accounts.startup;
+    startup.require_current()?;
+    let now = ctx.accounts.clock.unix_timestamp;
+    ctx.accounts.attestation.verify(now)?;
+
+    // Caps apply across all of the investor's transactions, not just this one
+    let tier_limit = config.tier_limit(ctx.accounts.attestation.tier)?;
+    ctx.accounts
+        .investor_profile
+        .record_investment(amount, now, &tier_limit)?;
+
+    require!(startup.status == StartupStatus::Open, ErrorCode::StartupNotOpen);
+    require!(
+        now >= startup.opens_at && now <= startup.closes_at,
+        ErrorCode::StartupNotOpen
+    );
+
+    // ===== FEATURE 1: USDC TOKEN TRANSFER (CPI) =====
+    // This executes the payment in the same atomic transaction
+    // The net principal goes to escrow, the platform fee (if any) to the treasury
+    let escrow_balance_before = ctx.accounts.program_escrow_ata.amount;
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.investor_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.program_escrow_ata.to_account_info(),
+        authority: ctx.accounts.investor.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    // Execute the USDC transfer
+    token_interface::transfer_checked(cpi_ctx, escrow_amount, ctx.accounts.mint.decimals)?;
+
+    // Token-2022 transfer fees are withheld from the escrow's side,
+    // so the principal is what escrow actually received
+    ctx.accounts.program_escrow_ata.reload()?;
+    let principal = ctx.accounts.program_escrow_ata
+        .amount
+        .checked_sub(escrow_balance_before)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    let new_total_raised = ctx.accounts.startup
+        .total_raised
+        .checked_add(principal)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    // Refunded principal frees room under the cap
+    let new_net_raised = ctx.accounts.startup
+        .net_raised()?
+        .checked_add(principal)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    require!(
+        new_net_raised <= ctx.accounts.startup.hard_cap,
+        ErrorCode::HardCapExceeded
+    );
+    let shares = ctx.accounts.startup.shares_for(principal, ctx.accounts.share_mint.decimals)?;
+    require!(shares > 0, ErrorCode::AmountBelowSharePrice);
+
+    if fee > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.investor_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.treasury_ata.to_account_info(),
+            authority: ctx.accounts.investor.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token_interface::transfer_checked(cpi_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    // ===== FEATURE 2: SHARE ISSUANCE =====
+    // Shares land in the investor's wallet; distributions follow share balances
+    mint_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.investor_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+
+    // ===== FEATURE 3: IMMUTABLE INVESTMENT RECORD =====
+    // Store the transaction details in a PDA for permanent record
+    let investor_profile = &mut ctx.accounts.investor_profile;
+    if investor_profile.investor == Pubkey::default() {
+        investor_profile.investor = ctx.accounts.investor.key();
+        investor_profile.bump = ctx.bumps.investor_profile;
+        investor_profile.version = ACCOUNT_VERSION;
+    }
+    let position_index = investor_profile.investment_count;
+    investor_profile.investment_count = position_index
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let expected_return = ctx.accounts.startup.expected_return;
+    let investment_record = &mut ctx.accounts.investment_record;
+    investment_record.investor = ctx.accounts.investor.key();
+    investment_record.startup_id = startup_id;
+    investment_record.mint = ctx.accounts.mint.key();
+    investment_record.position_index = position_index;
+    investment_record.gross_usd = amount;
+    investment_record.principal_usd = principal;
+    investment_record.shares = shares;
+    investment_record.investment_date = ctx.accounts.clock.unix_timestamp;
+    investment_record.expected_return = expected_return;
+    investment_record.status = InvestmentStatus::Active;
+    investment_record.reward_debt = ctx.accounts.startup.accumulated_returns(shares)?;
+    investment_record.unclaimed_returns = 0;
+    investment_record.returns_claimed = 0;
+    investment_record.settled_amount = 0;
+    investment_record.shortfall = 0;
+    investment_record.bump = ctx.bumps.investment_record;
+    investment_record.version = ACCOUNT_VERSION;
+    investment_record.rent_payer = ctx.accounts.investor.key();
+    let amount_due = investment_record.amount_due()?;
+
+    // ===== UPDATE STARTUP & ESCROW STATE =====
+    // Per-startup totals and the per-mint escrow total move together
+    ctx.accounts.startup.total_raised = new_total_raised;
+    ctx.accounts.startup.total_owed = ctx.accounts.startup
+        .total_owed
+        .checked_add(amount_due)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    ctx.accounts.startup.active_shares = ctx.accounts.startup
+        .active_shares
+        .checked_add(shares)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.total_escrow = accepted_mint
+        .total_escrow
+        .checked_add(principal)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    accepted_mint.total_fees_collected = accepted_mint
+        .total_fees_collected
+        .checked_add(fee)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.active_investments = escrow_state
+        .active_investments
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    // ===== EVENTS =====
+    emit!(InvestmentMade {
+        investor: ctx.accounts.investor.key(),
+        startup_id,
+        investment_record: ctx.accounts.investment_record.key(),
+        position_index,
+        gross_amount: amount,
+        fee,
+        principal,
+        shares,
+        expected_return,
+        startup_total_raised: new_total_raised,
+        mint: accepted_mint.mint,
+        total_escrow: accepted_mint.total_escrow,
+        timestamp: ctx.accounts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_investment(mut ctx: Context<CancelInvestment>) -> Result<()> {
+    let record = &ctx.accounts.investment_record;
+    let authority = ctx.accounts.authority.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(record.status == InvestmentStatus::Active, ErrorCode::InvestmentNotActive);
+
+    let is_investor = authority == record.investor;
+    let is_admin = authority == ctx.accounts.config.admin;
+    require!(is_investor || is_admin, ErrorCode::Unauthorized);
+
+    let cooling_off_ends = record
+        .investment_date
+        .checked_add(ctx.accounts.config.cooling_off_period)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    // Once the raise is finalized its escrow may be released, so the
+    // cooling-off right ends with it
+    let raise_pending = matches!(
+        ctx.accounts.startup.status,
+        StartupStatus::Open | StartupStatus::Closed
+    );
+    let within_cooling_off = is_investor && raise_pending && now <= cooling_off_ends;
+    let startup_cancelled = ctx.accounts.startup.status == StartupStatus::Cancelled;
+    require!(
+        within_cooling_off || startup_cancelled,
+        ErrorCode::CancellationNotAllowed
+    );
+
+    let principal = refund_principal(&mut ctx, InvestmentStatus::Cancelled)?;
+
+    emit!(InvestmentCancelled {
+        investor: ctx.accounts.investment_record.investor,
+        startup_id: ctx.accounts.investment_record.startup_id,
+        investment_record: ctx.accounts.investment_record.key(),
+        cancelled_by: authority,
+        refunded: principal,
+        total_escrow: ctx.accounts.accepted_mint.total_escrow,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn refund_investment(mut ctx: Context<CancelInvestment>) -> Result<()> {
+    require!(
+        ctx.accounts.investment_record.status == InvestmentStatus::Active,
+        ErrorCode::InvestmentNotActive
+    );
+    require!(
+        ctx.accounts.startup.status == StartupStatus::Failed,
+        ErrorCode::RaiseNotFailed
+    );
+
+    let principal = refund_principal(&mut ctx, InvestmentStatus::Refunded)?;
+
+    emit!(InvestmentRefunded {
+        investor: ctx.accounts.investment_record.investor,
+        startup_id: ctx.accounts.investment_record.startup_id,
+        investment_record: ctx.accounts.investment_record.key(),
+        refunded_by: ctx.accounts.authority.key(),
+        refunded: principal,
+        total_escrow: ctx.accounts.accepted_mint.total_escrow,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Return an investment's principal (see `Startup::refund_for`) from escrow to
+/// the investor, move the record to `status` and update the startup and escrow totals
+pub(crate) fn refund_principal(ctx: &mut Context<CancelInvestment>, status: InvestmentStatus) -> Result<u64> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_REFUNDS)?;
+    ctx.accounts.startup.require_current()?;
+
+    let principal = ctx
+        .accounts
+        .startup
+        .refund_for(ctx.accounts.investment_record.principal_usd)?;
+    require!(
+        principal <= ctx.accounts.startup.escrow_balance()?,
+        ErrorCode::InsufficientStartupFunds
+    );
+
+    let escrow_bump = ctx.bumps.escrow_pda;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"escrow_authority", &[escrow_bump]]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.program_escrow_ata.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.investor_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_pda.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, principal, ctx.accounts.mint.decimals)?;
+
+    let shares = ctx.accounts.investment_record.shares;
+    burn_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.investor_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+
+    // Returns distributed before the refund stay claimable
+    ctx.accounts.investment_record.accrue_returns(&ctx.accounts.startup)?;
+    ctx.accounts.investment_record.transition(status)?;
+    // A refunded position will never be settled
+    ctx.accounts.startup.total_owed = ctx.accounts.startup
+        .total_owed
+        .checked_sub(ctx.accounts.investment_record.amount_due()?)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    ctx.accounts.startup.total_refunded = ctx.accounts.startup
+        .total_refunded
+        .checked_add(principal)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    ctx.accounts.startup.active_shares = ctx.accounts.startup
+        .active_shares
+        .checked_sub(shares)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.total_escrow = accepted_mint
+        .total_escrow
+        .checked_sub(principal)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.active_investments = escrow_state
+        .active_investments
+        .checked_sub(1)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    Ok(principal)
+}
@@ -0,0 +1,473 @@
+//! Secondary-market listings of positions
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::{burn_shares, mint_shares};
+
+/// List a position's shares for sale
+#[derive(Accounts)]
+pub struct ListPosition<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    /// One listing per position (seeds: ["listing", investment_record])
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::LEN,
+        seeds = [b"listing", investment_record.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw a listing (seller any time, anyone once expired); rent returns to the seller
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the listing rent, verified against the listing
+    #[account(mut, address = listing.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", listing.investment_record.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+/// Buy shares from a listing: USDC to the seller, royalty to the startup,
+/// fee to the treasury, and the shares as a new position for the buyer
+/// Token accounts are boxed to keep the accounts struct within the SBF stack limit
+#[derive(Accounts)]
+pub struct FillListing<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Receives the listing rent once it is filled, verified against the listing
+    #[account(mut, address = listing.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", investment_record.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    /// Seller's position; shrinks by the shares sold
+    #[account(
+        mut,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    /// Buyer's profile - Created on first use, holds the position counter and caps
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = InvestorProfile::LEN,
+        seeds = [b"investor", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_profile: Account<'info, InvestorProfile>,
+
+    /// Buyer's KYC attestation - must be unrevoked and unexpired
+    #[account(
+        seeds = [b"attestation", buyer.key().as_ref()],
+        bump = buyer_attestation.bump
+    )]
+    pub buyer_attestation: Account<'info, InvestorAttestation>,
+
+    /// The bought shares as a position under the buyer's next position index
+    #[account(
+        init,
+        payer = buyer,
+        space = InvestmentAccount::LEN,
+        seeds = [
+            b"investment",
+            buyer.key().as_ref(),
+            &buyer_profile.investment_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_investment_record: Account<'info, InvestmentAccount>,
+
+    #[account(
+        seeds = [b"startup", investment_record.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    /// Allowlist entry holding the per-mint fee total
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", startup.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(address = startup.mint)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = listing.seller
+    )]
+    pub seller_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Startup payout wallet's token account (receives the royalty)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = startup.payout_wallet
+    )]
+    pub payout_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = fee_pda
+    )]
+    pub treasury_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"fee_authority"],
+        bump
+    )]
+    pub fee_pda: UncheckedAccount<'info>,
+
+    #[account(mut, address = startup.share_mint)]
+    pub share_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = share_mint,
+        associated_token::authority = listing.seller,
+        associated_token::token_program = share_token_program
+    )]
+    pub seller_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = share_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = share_token_program
+    )]
+    pub buyer_share_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub share_token_program: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn list_position(
+    ctx: Context<ListPosition>,
+    shares: u64,
+    price_per_share: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let record = &ctx.accounts.investment_record;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        ctx.accounts.seller.key() == record.investor,
+        ErrorCode::Unauthorized
+    );
+    require!(record.status == InvestmentStatus::Active, ErrorCode::InvestmentNotActive);
+    require!(
+        shares > 0 && shares <= record.shares && price_per_share > 0 && expires_at > now,
+        ErrorCode::InvalidListing
+    );
+
+    let listing = &mut ctx.accounts.listing;
+    listing.seller = record.investor;
+    listing.investment_record = record.key();
+    listing.startup_id = record.startup_id;
+    listing.shares_remaining = shares;
+    listing.price_per_share = price_per_share;
+    listing.created_at = now;
+    listing.expires_at = expires_at;
+    listing.bump = ctx.bumps.listing;
+    listing.version = ACCOUNT_VERSION;
+
+    emit!(PositionListed {
+        listing: listing.key(),
+        seller: listing.seller,
+        startup_id: listing.startup_id,
+        investment_record: listing.investment_record,
+        shares,
+        price_per_share,
+        expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.authority.key() == listing.seller || now > listing.expires_at,
+        ErrorCode::Unauthorized
+    );
+
+    emit!(ListingCancelled {
+        listing: listing.key(),
+        seller: listing.seller,
+        investment_record: listing.investment_record,
+        cancelled_by: ctx.accounts.authority.key(),
+        shares_remaining: listing.shares_remaining,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn fill_listing(
+    ctx: Context<FillListing>,
+    shares: u64,
+    max_price_per_share: u64,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_not_paused(ProgramConfig::PAUSE_TRANSFERS)?;
+    require!(ctx.accounts.accepted_mint.enabled, ErrorCode::MintNotAccepted);
+
+    let listing = &ctx.accounts.listing;
+    let record = &ctx.accounts.investment_record;
+    let buyer = ctx.accounts.buyer.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(now <= listing.expires_at, ErrorCode::ListingExpired);
+    require!(
+        listing.price_per_share <= max_price_per_share,
+        ErrorCode::PriceExceedsLimit
+    );
+    require!(record.investor == listing.seller, ErrorCode::InvalidListing);
+    require!(record.status == InvestmentStatus::Active, ErrorCode::InvestmentNotActive);
+    // Positions in a cancelled or failed raise are only good for a refund
+    require!(
+        matches!(
+            ctx.accounts.startup.status,
+            StartupStatus::Open | StartupStatus::Closed | StartupStatus::Funded
+        ),
+        ErrorCode::StartupNotTradable
+    );
+    require!(buyer != listing.seller, ErrorCode::InvalidReceiver);
+    require!(
+        shares > 0 && shares <= listing.shares_remaining && shares <= record.shares,
+        ErrorCode::InvalidListing
+    );
+    let unlocked_at = record
+        .investment_date
+        .checked_add(config.min_holding_period)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    require!(now >= unlocked_at, ErrorCode::HoldingPeriodActive);
+
+    ctx.accounts.buyer_attestation.verify(now)?;
+    let cost = listing.cost(shares, ctx.accounts.share_mint.decimals)?;
+    require!(cost > 0, ErrorCode::InvalidAmount);
+    let tier_limit = config.tier_limit(ctx.accounts.buyer_attestation.tier)?;
+    ctx.accounts
+        .buyer_profile
+        .record_investment(cost, now, &tier_limit)?;
+
+    let fee = config.platform_fee(cost)?;
+    let royalty = u64::try_from(
+        (cost as u128)
+            .checked_mul(ctx.accounts.startup.royalty_bps as u128)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / 10_000,
+    )
+    .map_err(|_| error!(ErrorCode::Overflow))?;
+    let proceeds = cost
+        .checked_sub(fee)
+        .and_then(|proceeds| proceeds.checked_sub(royalty))
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    // ===== USDC LEGS =====
+    let payments = [
+        (ctx.accounts.seller_token_account.to_account_info(), proceeds),
+        (ctx.accounts.payout_token_account.to_account_info(), royalty),
+        (ctx.accounts.treasury_ata.to_account_info(), fee),
+    ];
+    for (to, amount) in payments {
+        if amount == 0 {
+            continue;
+        }
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.buyer_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to,
+            authority: ctx.accounts.buyer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+    }
+
+    // ===== SHARE LEG =====
+    burn_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.seller_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+    mint_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.buyer_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+
+    // ===== SPLIT THE POSITION =====
+    // Returns accrued so far stay with the seller; the buyer earns from here on
+    ctx.accounts.investment_record.accrue_returns(&ctx.accounts.startup)?;
+    let record = &ctx.accounts.investment_record;
+    let principal = proportion(record.principal_usd, shares, record.shares)?;
+    let gross_usd = proportion(record.gross_usd, shares, record.shares)?;
+
+    let buyer_profile = &mut ctx.accounts.buyer_profile;
+    if buyer_profile.investor == Pubkey::default() {
+        buyer_profile.investor = buyer;
+        buyer_profile.bump = ctx.bumps.buyer_profile;
+        buyer_profile.version = ACCOUNT_VERSION;
+    }
+    let position_index = buyer_profile.investment_count;
+    buyer_profile.investment_count = position_index
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let new_record = &mut ctx.accounts.new_investment_record;
+    new_record.investor = buyer;
+    new_record.startup_id = record.startup_id;
+    new_record.mint = record.mint;
+    new_record.position_index = position_index;
+    new_record.gross_usd = gross_usd;
+    new_record.principal_usd = principal;
+    new_record.shares = shares;
+    new_record.investment_date = record.investment_date;
+    new_record.expected_return = record.expected_return;
+    new_record.status = InvestmentStatus::Active;
+    new_record.reward_debt = ctx.accounts.startup.accumulated_returns(shares)?;
+    new_record.unclaimed_returns = 0;
+    new_record.returns_claimed = 0;
+    new_record.settled_amount = 0;
+    new_record.shortfall = 0;
+    new_record.bump = ctx.bumps.new_investment_record;
+    new_record.version = ACCOUNT_VERSION;
+    new_record.rent_payer = buyer;
+
+    let record = &mut ctx.accounts.investment_record;
+    record.principal_usd = record
+        .principal_usd
+        .checked_sub(principal)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    record.gross_usd = record
+        .gross_usd
+        .checked_sub(gross_usd)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    record.shares = record
+        .shares
+        .checked_sub(shares)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    record.reward_debt = ctx.accounts.startup.accumulated_returns(record.shares)?;
+
+    // A fully sold position leaves the active set; the buyer's joins it
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    if record.shares == 0 {
+        record.transition(InvestmentStatus::Transferred)?;
+    } else {
+        escrow_state.active_investments = escrow_state
+            .active_investments
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::Overflow))?;
+    }
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.total_fees_collected = accepted_mint
+        .total_fees_collected
+        .checked_add(fee)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let listing = &mut ctx.accounts.listing;
+    listing.shares_remaining = listing
+        .shares_remaining
+        .checked_sub(shares)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    emit!(ListingFilled {
+        listing: listing.key(),
+        seller: listing.seller,
+        buyer,
+        startup_id: listing.startup_id,
+        investment_record: listing.investment_record,
+        new_investment_record: ctx.accounts.new_investment_record.key(),
+        shares,
+        price_per_share: listing.price_per_share,
+        cost,
+        royalty,
+        fee,
+        shares_remaining: listing.shares_remaining,
+        timestamp: now,
+    });
+
+    if listing.shares_remaining == 0 {
+        ctx.accounts
+            .listing
+            .close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! In-place migration of accounts to the current layout
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+/// Upgrade the program config from v1 (admin only)
+/// The config is read raw, since the admin check must not depend on its layout
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Admin; pays the rent for the added space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: The config PDA, verified by seeds; layout checked by the migration
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrade a program-wide account from v1 (admin only); run `migrate_config` first
+#[derive(Accounts)]
+pub struct MigrateAdminAccount<'info> {
+    /// Admin; pays the rent for the added space
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Owner, discriminator and size are checked by the migration
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrade a per-investor or per-startup account from v1 (permissionless)
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// Anyone; pays the rent for the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Owner, discriminator and size are checked by the migration
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config = ctx.accounts.config.to_account_info();
+    let admin = {
+        // `admin` is the config's first field in every layout
+        let data = config.try_borrow_data()?;
+        require!(data.len() >= 8 + 32, ErrorCode::InvalidMigrationTarget);
+        Pubkey::try_from(&data[8..8 + 32]).map_err(|_| error!(ErrorCode::InvalidMigrationTarget))?
+    };
+    require!(ctx.accounts.admin.key() == admin, ErrorCode::Unauthorized);
+
+    migrate_account(
+        &config,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        upgrade_v1_account_data::<v1::ProgramConfig>,
+    )
+}
+
+pub(crate) fn migrate_escrow_state(ctx: Context<MigrateAdminAccount>) -> Result<()> {
+    migrate_admin_account(ctx, upgrade_v1_account_data::<v1::EscrowState>)
+}
+
+pub(crate) fn migrate_accepted_mint(ctx: Context<MigrateAdminAccount>) -> Result<()> {
+    migrate_admin_account(ctx, upgrade_account_data::<AcceptedMint>)
+}
+
+pub(crate) fn migrate_startup(
+    ctx: Context<MigrateAdminAccount>,
+    expected_return: u8,
+    total_owed: u64,
+) -> Result<()> {
+    migrate_admin_account(ctx, |data| {
+        let mut startup = upgrade_account_data::<Startup>(data)?;
+        startup.backfill_v2(expected_return, total_owed)?;
+        Ok(startup)
+    })
+}
+
+pub(crate) fn migrate_milestone(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, upgrade_account_data::<Milestone>)
+}
+
+pub(crate) fn migrate_release_proposal(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, upgrade_account_data::<ReleaseProposal>)
+}
+
+pub(crate) fn migrate_investment(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, |data| {
+        let mut record = upgrade_v1_account_data::<v1::InvestmentAccount>(data)?;
+        if record.rent_payer == Pubkey::default() {
+            record.rent_payer = record.investor;
+        }
+        Ok(record)
+    })
+}
+
+pub(crate) fn migrate_investor_profile(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, upgrade_account_data::<InvestorProfile>)
+}
+
+pub(crate) fn migrate_attestation(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, upgrade_account_data::<InvestorAttestation>)
+}
+
+pub(crate) fn migrate_listing(ctx: Context<MigrateAccount>) -> Result<()> {
+    migrate_user_account(ctx, upgrade_account_data::<Listing>)
+}
+
+/// Admin-gated `migrate_account` for program-wide accounts
+pub(crate) fn migrate_admin_account<T: Versioned>(
+    ctx: Context<MigrateAdminAccount>,
+    upgrade: impl FnOnce(&[u8]) -> Result<T>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    migrate_account(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program,
+        upgrade,
+    )
+}
+
+/// Permissionless `migrate_account`; the payer only funds any added rent
+pub(crate) fn migrate_user_account<T: Versioned>(
+    ctx: Context<MigrateAccount>,
+    upgrade: impl FnOnce(&[u8]) -> Result<T>,
+) -> Result<()> {
+    migrate_account(
+        &ctx.accounts.account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        upgrade,
+    )
+}
+
+/// Decode an account with `upgrade`, grow it to its current size if needed
+/// (topping up rent from `payer`) and write it back at `ACCOUNT_VERSION`
+pub(crate) fn migrate_account<'info, T: Versioned>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    upgrade: impl FnOnce(&[u8]) -> Result<T>,
+) -> Result<()> {
+    require!(account.owner == &crate::ID, ErrorCode::InvalidMigrationTarget);
+    let migrated = upgrade(&account.try_borrow_data()?)?;
+
+    if account.data_len() < T::SPACE {
+        let rent = Rent::get()?.minimum_balance(T::SPACE);
+        let top_up = rent.saturating_sub(account.lamports());
+        if top_up > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            };
+            let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, top_up)?;
+        }
+        account.realloc(T::SPACE, true)?;
+    }
+    migrated.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        version: ACCOUNT_VERSION,
+        migrated_by: payer.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
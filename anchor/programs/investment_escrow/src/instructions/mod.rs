@@ -0,0 +1,126 @@
+//! Instruction account contexts and handlers, grouped by area, and the helpers they share
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, Burn, FreezeAccount, Mint, MintTo, ThawAccount, Token2022, TokenAccount,
+};
+
+use crate::state::*;
+
+mod admin;
+mod compliance;
+mod handover;
+mod invest;
+mod market;
+mod migrate;
+mod position;
+mod release;
+mod returns;
+mod startup;
+
+pub use admin::*;
+pub use compliance::*;
+pub use handover::*;
+pub use invest::*;
+pub use market::*;
+pub use migrate::*;
+pub use position::*;
+pub use release::*;
+pub use returns::*;
+pub use startup::*;
+
+/// Mint `shares` to a holder's share account. The account is left frozen, so
+/// shares only move through the program and balances always match the holder's
+/// active positions; the holder never signs, as the startup PDA is the mint's
+/// permanent delegate.
+pub(crate) fn mint_shares<'info>(
+    token_program: &Program<'info, Token2022>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    share_account: &InterfaceAccount<'info, TokenAccount>,
+    startup: &Account<'info, Startup>,
+    shares: u64,
+) -> Result<()> {
+    let startup_id = startup.startup_id;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"startup", startup_id.as_ref(), &[startup.bump]]];
+    let cpi_program = token_program.to_account_info();
+
+    if share_account.is_frozen() {
+        let cpi_accounts = ThawAccount {
+            account: share_account.to_account_info(),
+            mint: share_mint.to_account_info(),
+            authority: startup.to_account_info(),
+        };
+        token_interface::thaw_account(CpiContext::new_with_signer(
+            cpi_program.clone(),
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+    }
+
+    let cpi_accounts = MintTo {
+        mint: share_mint.to_account_info(),
+        to: share_account.to_account_info(),
+        authority: startup.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds),
+        shares,
+    )?;
+
+    let cpi_accounts = FreezeAccount {
+        account: share_account.to_account_info(),
+        mint: share_mint.to_account_info(),
+        authority: startup.to_account_info(),
+    };
+    token_interface::freeze_account(CpiContext::new_with_signer(
+        cpi_program,
+        cpi_accounts,
+        signer_seeds,
+    ))
+}
+
+/// Burn a position's `shares` from its holder's frozen share account as the
+/// startup PDA (freeze authority and permanent delegate), then refreeze the account
+pub(crate) fn burn_shares<'info>(
+    token_program: &Program<'info, Token2022>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    share_account: &InterfaceAccount<'info, TokenAccount>,
+    startup: &Account<'info, Startup>,
+    shares: u64,
+) -> Result<()> {
+    let startup_id = startup.startup_id;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"startup", startup_id.as_ref(), &[startup.bump]]];
+    let cpi_program = token_program.to_account_info();
+
+    let cpi_accounts = ThawAccount {
+        account: share_account.to_account_info(),
+        mint: share_mint.to_account_info(),
+        authority: startup.to_account_info(),
+    };
+    token_interface::thaw_account(CpiContext::new_with_signer(
+        cpi_program.clone(),
+        cpi_accounts,
+        signer_seeds,
+    ))?;
+
+    let cpi_accounts = Burn {
+        mint: share_mint.to_account_info(),
+        from: share_account.to_account_info(),
+        authority: startup.to_account_info(),
+    };
+    token_interface::burn(
+        CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts, signer_seeds),
+        shares,
+    )?;
+
+    let cpi_accounts = FreezeAccount {
+        account: share_account.to_account_info(),
+        mint: share_mint.to_account_info(),
+        authority: startup.to_account_info(),
+    };
+    token_interface::freeze_account(CpiContext::new_with_signer(
+        cpi_program,
+        cpi_accounts,
+        signer_seeds,
+    ))
+}
@@ -0,0 +1,236 @@
+//! Transferring and closing investment positions
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::{burn_shares, mint_shares};
+
+/// Re-home an active position to another wallet
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    /// Current holder of the position; pays for the receiver's new accounts
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: New holder's wallet; only its key is used, to derive the receiver's accounts
+    pub receiver: UncheckedAccount<'info>,
+
+    /// Position being transferred (marked Transferred)
+    #[account(
+        mut,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    /// Receiver's profile - Created on first use, holds the position counter
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = InvestorProfile::LEN,
+        seeds = [b"investor", receiver.key().as_ref()],
+        bump
+    )]
+    pub receiver_profile: Account<'info, InvestorProfile>,
+
+    /// Receiver's KYC attestation - must be unrevoked and unexpired
+    #[account(
+        seeds = [b"attestation", receiver.key().as_ref()],
+        bump = receiver_attestation.bump
+    )]
+    pub receiver_attestation: Account<'info, InvestorAttestation>,
+
+    /// The position re-created under the receiver's next position index
+    #[account(
+        init,
+        payer = owner,
+        space = InvestmentAccount::LEN,
+        seeds = [
+            b"investment",
+            receiver.key().as_ref(),
+            &receiver_profile.investment_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_investment_record: Account<'info, InvestmentAccount>,
+
+    #[account(
+        seeds = [b"startup", investment_record.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, address = startup.share_mint)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = share_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = share_token_program
+    )]
+    pub owner_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = share_mint,
+        associated_token::authority = receiver,
+        associated_token::token_program = share_token_program
+    )]
+    pub receiver_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub share_token_program: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a finished investment record and return its rent to whoever paid it
+#[derive(Accounts)]
+pub struct CloseInvestment<'info> {
+    pub investor: Signer<'info>,
+
+    /// CHECK: Receives the record's rent, verified against the record
+    #[account(mut, address = investment_record.rent_recipient())]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+}
+
+pub(crate) fn transfer_position(ctx: Context<TransferPosition>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    config.require_not_paused(ProgramConfig::PAUSE_TRANSFERS)?;
+
+    let record = &ctx.accounts.investment_record;
+    let owner = ctx.accounts.owner.key();
+    let receiver = ctx.accounts.receiver.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(owner == record.investor, ErrorCode::Unauthorized);
+    require!(record.status == InvestmentStatus::Active, ErrorCode::InvestmentNotActive);
+    require!(receiver != owner, ErrorCode::InvalidReceiver);
+    let unlocked_at = record
+        .investment_date
+        .checked_add(config.min_holding_period)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    require!(now >= unlocked_at, ErrorCode::HoldingPeriodActive);
+    ctx.accounts.receiver_attestation.verify(now)?;
+
+    let shares = record.shares;
+    burn_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.owner_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+    mint_shares(
+        &ctx.accounts.share_token_program,
+        &ctx.accounts.share_mint,
+        &ctx.accounts.receiver_share_account,
+        &ctx.accounts.startup,
+        shares,
+    )?;
+
+    let receiver_profile = &mut ctx.accounts.receiver_profile;
+    if receiver_profile.investor == Pubkey::default() {
+        receiver_profile.investor = receiver;
+        receiver_profile.bump = ctx.bumps.receiver_profile;
+        receiver_profile.version = ACCOUNT_VERSION;
+    }
+    let position_index = receiver_profile.investment_count;
+    receiver_profile.investment_count = position_index
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    // Accounting travels with the shares: unclaimed returns, the reward
+    // checkpoint and the lifetime claim and settlement totals
+    let record = &ctx.accounts.investment_record;
+    let new_record = &mut ctx.accounts.new_investment_record;
+    new_record.investor = receiver;
+    new_record.startup_id = record.startup_id;
+    new_record.mint = record.mint;
+    new_record.position_index = position_index;
+    new_record.gross_usd = record.gross_usd;
+    new_record.principal_usd = record.principal_usd;
+    new_record.shares = shares;
+    new_record.investment_date = record.investment_date;
+    new_record.expected_return = record.expected_return;
+    new_record.status = InvestmentStatus::Active;
+    new_record.reward_debt = record.reward_debt;
+    new_record.unclaimed_returns = record.unclaimed_returns;
+    new_record.returns_claimed = record.returns_claimed;
+    new_record.settled_amount = record.settled_amount;
+    new_record.shortfall = record.shortfall;
+    new_record.bump = ctx.bumps.new_investment_record;
+    new_record.version = ACCOUNT_VERSION;
+    new_record.rent_payer = owner;
+
+    let record = &mut ctx.accounts.investment_record;
+    record.transition(InvestmentStatus::Transferred)?;
+    record.unclaimed_returns = 0;
+
+    emit!(PositionTransferred {
+        startup_id: record.startup_id,
+        from: owner,
+        to: receiver,
+        investment_record: record.key(),
+        new_investment_record: ctx.accounts.new_investment_record.key(),
+        principal: record.principal_usd,
+        shares,
+        investment_date: record.investment_date,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn close_investment(ctx: Context<CloseInvestment>) -> Result<()> {
+    let record = &ctx.accounts.investment_record;
+    require!(
+        ctx.accounts.investor.key() == record.investor,
+        ErrorCode::Unauthorized
+    );
+    record.require_closable()?;
+
+    emit!(InvestmentClosed {
+        investor: record.investor,
+        startup_id: record.startup_id,
+        investment_record: record.key(),
+        position_index: record.position_index,
+        mint: record.mint,
+        status: record.status,
+        gross_amount: record.gross_usd,
+        principal: record.principal_usd,
+        returns_claimed: record.returns_claimed,
+        settled_amount: record.settled_amount,
+        shortfall: record.shortfall,
+        investment_date: record.investment_date,
+        closed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,556 @@
+//! Milestones, release proposals and the release of escrowed funds
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+/// Release funds from escrow (admin/authority only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct ReleaseFunds<'info> {
+    /// Anyone may execute a proposal once it has enough approvals
+    pub executor: Signer<'info>,
+
+    #[account(mut, seeds = [b"escrow"], bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// Startup whose escrowed funds are being released
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    /// Allowlist entry holding the per-mint escrow totals
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", startup.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Approved release proposal being executed
+    #[account(
+        mut,
+        seeds = [
+            b"release_proposal",
+            startup_id.as_ref(),
+            &release_proposal.proposal_id.to_le_bytes()
+        ],
+        bump = release_proposal.bump
+    )]
+    pub release_proposal: Account<'info, ReleaseProposal>,
+
+    /// Milestone whose tranche the proposal releases
+    #[account(
+        mut,
+        seeds = [b"milestone", startup_id.as_ref(), &[release_proposal.milestone_index]],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow_pda,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Startup's payout token account named in the proposal
+    #[account(
+        mut,
+        address = release_proposal.destination,
+        token::mint = mint,
+        token::authority = startup.payout_wallet
+    )]
+    pub destination_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    /// Fee Treasury Token Account (receives platform fees)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = fee_pda
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"fee_authority"],
+        bump
+    )]
+    pub fee_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Create a milestone for a startup (admin only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct CreateMilestone<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Milestone::LEN,
+        seeds = [b"milestone", startup_id.as_ref(), &[startup.milestone_count]],
+        bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approve or reject a pending milestone (release authority only)
+#[derive(Accounts)]
+pub struct ReviewMilestone<'info> {
+    pub release_authority: Signer<'info>,
+
+    #[account(seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [b"milestone", milestone.startup_id.as_ref(), &[milestone.index]],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", milestone.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+}
+
+/// Create a release proposal for a milestone tranche (approvers only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey, milestone_index: u8)]
+pub struct ProposeRelease<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = ReleaseProposal::LEN,
+        seeds = [
+            b"release_proposal",
+            startup_id.as_ref(),
+            &startup.release_proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub release_proposal: Account<'info, ReleaseProposal>,
+
+    /// Approved milestone whose tranche is proposed for release
+    #[account(
+        seeds = [b"milestone", startup_id.as_ref(), &[milestone_index]],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    /// Startup's payout token account (must be owned by its payout wallet)
+    #[account(
+        token::mint = mint,
+        token::authority = startup.payout_wallet
+    )]
+    pub destination_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approve or cancel a pending release proposal
+#[derive(Accounts)]
+pub struct ReviewRelease<'info> {
+    /// Approver (approve) or proposer/admin/release authority (cancel)
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"release_proposal",
+            release_proposal.startup_id.as_ref(),
+            &release_proposal.proposal_id.to_le_bytes()
+        ],
+        bump = release_proposal.bump
+    )]
+    pub release_proposal: Account<'info, ReleaseProposal>,
+}
+
+pub(crate) fn release_funds(
+    ctx: Context<ReleaseFunds>,
+    startup_id: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_RELEASES)?;
+
+    let proposal = &ctx.accounts.release_proposal;
+    require!(
+        proposal.status == ReleaseProposalStatus::Pending,
+        ErrorCode::ReleaseProposalNotPending
+    );
+    require!(
+        Clock::get()?.unix_timestamp <= proposal.expires_at,
+        ErrorCode::ReleaseProposalExpired
+    );
+    require!(
+        proposal.approval_count(&ctx.accounts.config)
+            >= ctx.accounts.config.approval_threshold as usize,
+        ErrorCode::InsufficientApprovals
+    );
+    require!(
+        ctx.accounts.milestone.state == MilestoneState::Approved,
+        ErrorCode::MilestoneNotApproved
+    );
+    let amount = proposal.amount;
+
+    require!(
+        ctx.accounts.startup.status == StartupStatus::Funded,
+        ErrorCode::RaiseNotFunded
+    );
+
+    require!(
+        amount <= ctx.accounts.startup.escrow_balance()?,
+        ErrorCode::InsufficientStartupFunds
+    );
+
+    require!(
+        ctx.accounts.escrow_ata.amount >= amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    // Create a signer bump for the escrow PDA
+    let escrow_bump = ctx.bumps.escrow_pda;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"escrow_authority", &[escrow_bump]]];
+
+    let fee = match ctx.accounts.config.fee_mode {
+        FeeMode::OnInvest => 0,
+        FeeMode::OnRelease => ctx.accounts.config.platform_fee(amount)?,
+    };
+    let payout = amount
+        .checked_sub(fee)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    // Transfer USDC from escrow to destination
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.escrow_ata.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.destination_ata.to_account_info(),
+        authority: ctx.accounts.escrow_pda.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, payout, ctx.accounts.mint.decimals)?;
+
+    // Transfer the platform fee from escrow to the treasury
+    if fee > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow_ata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.treasury_ata.to_account_info(),
+            authority: ctx.accounts.escrow_pda.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        token_interface::transfer_checked(cpi_ctx, fee, ctx.accounts.mint.decimals)?;
+    }
+
+    // Update startup and escrow state
+    ctx.accounts.startup.total_released = ctx.accounts.startup
+        .total_released
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    ctx.accounts.accepted_mint.total_escrow = ctx.accounts.accepted_mint
+        .total_escrow
+        .checked_sub(amount)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    ctx.accounts.accepted_mint.total_fees_collected = ctx.accounts.accepted_mint
+        .total_fees_collected
+        .checked_add(fee)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    ctx.accounts.release_proposal.status = ReleaseProposalStatus::Executed;
+    ctx.accounts.milestone.state = MilestoneState::Released;
+
+    emit!(FundsReleased {
+        startup_id,
+        proposal_id: ctx.accounts.release_proposal.proposal_id,
+        milestone_index: ctx.accounts.milestone.index,
+        destination: ctx.accounts.destination_ata.key(),
+        amount,
+        fee,
+        startup_escrow_balance: ctx.accounts.startup.escrow_balance()?,
+        mint: ctx.accounts.accepted_mint.mint,
+        total_escrow: ctx.accounts.accepted_mint.total_escrow,
+        executor: ctx.accounts.executor.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn create_milestone(
+    ctx: Context<CreateMilestone>,
+    startup_id: Pubkey,
+    description_hash: [u8; 32],
+    percent_bps: u16,
+    due_date: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+
+    let startup = &mut ctx.accounts.startup;
+    let milestone_bps_total = startup
+        .milestone_bps_total
+        .checked_add(percent_bps)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    require!(
+        percent_bps > 0 && milestone_bps_total <= 10_000,
+        ErrorCode::InvalidMilestonePercent
+    );
+
+    let index = startup.milestone_count;
+    startup.milestone_count = index
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    startup.milestone_bps_total = milestone_bps_total;
+
+    let milestone = &mut ctx.accounts.milestone;
+    milestone.startup_id = startup_id;
+    milestone.index = index;
+    milestone.description_hash = description_hash;
+    milestone.percent_bps = percent_bps;
+    milestone.due_date = due_date;
+    milestone.state = MilestoneState::Pending;
+    milestone.bump = ctx.bumps.milestone;
+    milestone.version = ACCOUNT_VERSION;
+
+    emit!(MilestoneCreated {
+        startup_id,
+        index,
+        description_hash,
+        percent_bps,
+        due_date,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn approve_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+    review_milestone(ctx, MilestoneState::Approved)
+}
+
+pub(crate) fn reject_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+    review_milestone(ctx, MilestoneState::Rejected)
+}
+
+pub(crate) fn propose_release(
+    ctx: Context<ProposeRelease>,
+    startup_id: Pubkey,
+    milestone_index: u8,
+) -> Result<()> {
+    let proposer = ctx.accounts.proposer.key();
+    require!(
+        ctx.accounts.config.is_release_approver(&proposer),
+        ErrorCode::NotReleaseApprover
+    );
+    require!(
+        ctx.accounts.startup.status == StartupStatus::Funded,
+        ErrorCode::RaiseNotFunded
+    );
+    require!(
+        ctx.accounts.milestone.state == MilestoneState::Approved,
+        ErrorCode::MilestoneNotApproved
+    );
+
+    let amount = ctx
+        .accounts
+        .milestone
+        .tranche(ctx.accounts.startup.net_raised()?)?;
+    require!(
+        amount > 0 && amount <= ctx.accounts.startup.escrow_balance()?,
+        ErrorCode::InsufficientStartupFunds
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let expires_at = now
+        .checked_add(ctx.accounts.config.release_proposal_ttl)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let startup = &mut ctx.accounts.startup;
+    let proposal_id = startup.release_proposal_count;
+    startup.release_proposal_count = proposal_id
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    let proposal = &mut ctx.accounts.release_proposal;
+    proposal.startup_id = startup_id;
+    proposal.proposal_id = proposal_id;
+    proposal.milestone_index = milestone_index;
+    proposal.amount = amount;
+    proposal.destination = ctx.accounts.destination_ata.key();
+    proposal.proposer = proposer;
+    proposal.approvals = vec![proposer];
+    proposal.created_at = now;
+    proposal.expires_at = expires_at;
+    proposal.status = ReleaseProposalStatus::Pending;
+    proposal.bump = ctx.bumps.release_proposal;
+    proposal.version = ACCOUNT_VERSION;
+
+    emit!(ReleaseProposed {
+        startup_id,
+        proposal_id,
+        milestone_index,
+        amount,
+        destination: proposal.destination,
+        proposer,
+        expires_at,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn approve_release(ctx: Context<ReviewRelease>) -> Result<()> {
+    let approver = ctx.accounts.authority.key();
+    require!(
+        ctx.accounts.config.is_release_approver(&approver),
+        ErrorCode::NotReleaseApprover
+    );
+
+    let proposal = &mut ctx.accounts.release_proposal;
+    require!(
+        proposal.status == ReleaseProposalStatus::Pending,
+        ErrorCode::ReleaseProposalNotPending
+    );
+    require!(
+        Clock::get()?.unix_timestamp <= proposal.expires_at,
+        ErrorCode::ReleaseProposalExpired
+    );
+    require!(
+        !proposal.approvals.contains(&approver),
+        ErrorCode::AlreadyApproved
+    );
+
+    proposal.approvals.push(approver);
+
+    emit!(ReleaseApproved {
+        startup_id: proposal.startup_id,
+        proposal_id: proposal.proposal_id,
+        approver,
+        approvals: proposal.approval_count(&ctx.accounts.config) as u8,
+        approval_threshold: ctx.accounts.config.approval_threshold,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cancel_release(ctx: Context<ReviewRelease>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let proposal = &mut ctx.accounts.release_proposal;
+    require!(
+        authority == proposal.proposer
+            || authority == ctx.accounts.config.admin
+            || authority == ctx.accounts.escrow_state.release_authority,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        proposal.status == ReleaseProposalStatus::Pending,
+        ErrorCode::ReleaseProposalNotPending
+    );
+
+    proposal.status = ReleaseProposalStatus::Cancelled;
+
+    emit!(ReleaseCancelled {
+        startup_id: proposal.startup_id,
+        proposal_id: proposal.proposal_id,
+        cancelled_by: authority,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn review_milestone(ctx: Context<ReviewMilestone>, state: MilestoneState) -> Result<()> {
+    require!(
+        ctx.accounts.release_authority.key() == ctx.accounts.escrow_state.release_authority,
+        ErrorCode::Unauthorized
+    );
+
+    let milestone = &mut ctx.accounts.milestone;
+    require!(
+        milestone.state == MilestoneState::Pending,
+        ErrorCode::MilestoneNotPending
+    );
+    milestone.state = state;
+
+    if state == MilestoneState::Rejected {
+        let startup = &mut ctx.accounts.startup;
+        startup.milestone_bps_total = startup
+            .milestone_bps_total
+            .checked_sub(milestone.percent_bps)
+            .ok_or(error!(ErrorCode::Underflow))?;
+    }
+
+    emit!(MilestoneReviewed {
+        startup_id: milestone.startup_id,
+        index: milestone.index,
+        state,
+        reviewed_by: ctx.accounts.release_authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
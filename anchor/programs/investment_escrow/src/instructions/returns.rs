@@ -0,0 +1,523 @@
+//! Returns, repayments and settlement
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, Mint, Token2022, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+use super::burn_shares;
+
+/// Deposit repayments or revenue share for a startup's investors
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct DepositReturns<'info> {
+    /// Startup payout wallet or admin
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = depositor
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-startup distribution vault (seeds: ["distribution_vault", startup_id])
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        seeds = [b"distribution_vault", startup_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_pda,
+        token::token_program = token_program
+    )]
+    pub distribution_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit principal repayments for settling a startup's matured investments
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct DepositRepayment<'info> {
+    /// Startup payout wallet or admin
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = depositor
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-startup repayment vault (seeds: ["repayment_vault", startup_id])
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        seeds = [b"repayment_vault", startup_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_pda,
+        token::token_program = token_program
+    )]
+    pub repayment_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Settle a matured investment from the startup's repayment vault (investor or admin)
+#[derive(Accounts)]
+pub struct SettleInvestment<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", investment_record.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut, seeds = [b"escrow"], bump = escrow_state.bump)]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [b"repayment_vault", investment_record.startup_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_pda
+    )]
+    pub repayment_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Investor's USDC Token Account (receives the settlement)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = investment_record.investor
+    )]
+    pub investor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, address = startup.share_mint)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Position holder's share account (shares are burned on exit)
+    #[account(
+        mut,
+        associated_token::mint = share_mint,
+        associated_token::authority = investment_record.investor,
+        associated_token::token_program = share_token_program
+    )]
+    pub investor_share_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub share_token_program: Program<'info, Token2022>,
+}
+
+/// Claim an investment's pro-rata share of distributed returns
+#[derive(Accounts)]
+pub struct ClaimReturns<'info> {
+    pub investor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"investment",
+            investment_record.investor.as_ref(),
+            &investment_record.position_index.to_le_bytes()
+        ],
+        bump = investment_record.bump
+    )]
+    pub investment_record: Account<'info, InvestmentAccount>,
+
+    #[account(
+        seeds = [b"startup", investment_record.startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", investment_record.startup_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_pda
+    )]
+    pub distribution_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = investor
+    )]
+    pub investor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: This is a PDA, verified by seeds
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump
+    )]
+    pub escrow_pda: UncheckedAccount<'info>,
+
+    #[account(address = startup.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn deposit_returns(
+    ctx: Context<DepositReturns>,
+    startup_id: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_DISTRIBUTIONS)?;
+
+    let depositor = ctx.accounts.depositor.key();
+    require!(
+        depositor == ctx.accounts.startup.payout_wallet || depositor == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.startup.active_shares > 0,
+        ErrorCode::NoActiveInvestments
+    );
+
+    let vault_balance_before = ctx.accounts.distribution_vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.depositor_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.distribution_vault.to_account_info(),
+        authority: ctx.accounts.depositor.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    // Credit what the vault received, net of any Token-2022 transfer fee
+    ctx.accounts.distribution_vault.reload()?;
+    let amount = ctx.accounts.distribution_vault
+        .amount
+        .checked_sub(vault_balance_before)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    let startup = &mut ctx.accounts.startup;
+    let increment = (amount as u128)
+        .checked_mul(Startup::REWARD_PRECISION)
+        .ok_or(error!(ErrorCode::Overflow))?
+        / startup.active_shares as u128;
+    startup.reward_per_share = startup
+        .reward_per_share
+        .checked_add(increment)
+        .ok_or(error!(ErrorCode::Overflow))?;
+    startup.total_distributed = startup
+        .total_distributed
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    emit!(ReturnsDeposited {
+        startup_id,
+        depositor,
+        amount,
+        reward_per_share: startup.reward_per_share,
+        active_shares: startup.active_shares,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn deposit_repayment(
+    ctx: Context<DepositRepayment>,
+    startup_id: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_DISTRIBUTIONS)?;
+
+    let depositor = ctx.accounts.depositor.key();
+    require!(
+        depositor == ctx.accounts.startup.payout_wallet || depositor == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let vault_balance_before = ctx.accounts.repayment_vault.amount;
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.depositor_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.repayment_vault.to_account_info(),
+        authority: ctx.accounts.depositor.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    // Credit what the vault received, net of any Token-2022 transfer fee
+    ctx.accounts.repayment_vault.reload()?;
+    let amount = ctx.accounts.repayment_vault
+        .amount
+        .checked_sub(vault_balance_before)
+        .ok_or(error!(ErrorCode::Underflow))?;
+
+    let startup = &mut ctx.accounts.startup;
+    startup.total_repaid = startup
+        .total_repaid
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    emit!(RepaymentDeposited {
+        startup_id,
+        depositor,
+        amount,
+        total_repaid: startup.total_repaid,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn settle_investment(ctx: Context<SettleInvestment>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_DISTRIBUTIONS)?;
+    ctx.accounts.startup.require_current()?;
+
+    let record = &ctx.accounts.investment_record;
+    let authority = ctx.accounts.authority.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let first_settlement = record.status == InvestmentStatus::Active;
+    require!(
+        first_settlement || record.status == InvestmentStatus::Defaulted,
+        ErrorCode::InvestmentNotActive
+    );
+    require!(
+        authority == record.investor || authority == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.startup.status == StartupStatus::Funded,
+        ErrorCode::RaiseNotFunded
+    );
+    require!(
+        now >= record.maturity_date(ctx.accounts.startup.term_length)?,
+        ErrorCode::InvestmentNotMatured
+    );
+
+    let amount_due = record.amount_due()?;
+    let settled_amount = ctx.accounts.startup.repaid_share(amount_due)?;
+    let paid = settled_amount
+        .checked_sub(record.settled_amount)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    let shortfall = amount_due
+        .checked_sub(settled_amount)
+        .ok_or(error!(ErrorCode::Underflow))?;
+    require!(first_settlement || paid > 0, ErrorCode::NothingToSettle);
+
+    if paid > 0 {
+        let escrow_bump = ctx.bumps.escrow_pda;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow_authority", &[escrow_bump]]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.repayment_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.investor_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_pda.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        token_interface::transfer_checked(cpi_ctx, paid, ctx.accounts.mint.decimals)?;
+    }
+
+    // The position leaves the active set on its first settlement
+    if first_settlement {
+        let shares = ctx.accounts.investment_record.shares;
+        burn_shares(
+            &ctx.accounts.share_token_program,
+            &ctx.accounts.share_mint,
+            &ctx.accounts.investor_share_account,
+            &ctx.accounts.startup,
+            shares,
+        )?;
+
+        // Returns distributed before settlement stay claimable
+        ctx.accounts.investment_record.accrue_returns(&ctx.accounts.startup)?;
+        ctx.accounts.startup.active_shares = ctx.accounts.startup
+            .active_shares
+            .checked_sub(shares)
+            .ok_or(error!(ErrorCode::Underflow))?;
+
+        let escrow_state = &mut ctx.accounts.escrow_state;
+        escrow_state.active_investments = escrow_state
+            .active_investments
+            .checked_sub(1)
+            .ok_or(error!(ErrorCode::Underflow))?;
+    }
+
+    // Update record and startup
+    let record = &mut ctx.accounts.investment_record;
+    let old_shortfall = record.shortfall;
+    let status = if shortfall == 0 {
+        InvestmentStatus::Completed
+    } else {
+        InvestmentStatus::Defaulted
+    };
+    if status != record.status {
+        record.transition(status)?;
+    }
+    record.settled_amount = settled_amount;
+    record.shortfall = shortfall;
+
+    let startup = &mut ctx.accounts.startup;
+    startup.total_shortfall = startup
+        .total_shortfall
+        .checked_sub(old_shortfall)
+        .and_then(|total_shortfall| total_shortfall.checked_add(shortfall))
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    emit!(InvestmentSettled {
+        investor: record.investor,
+        startup_id: record.startup_id,
+        investment_record: record.key(),
+        settled_by: authority,
+        amount_due,
+        paid,
+        settled_amount,
+        shortfall,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_returns(ctx: Context<ClaimReturns>) -> Result<()> {
+    ctx.accounts
+        .config
+        .require_not_paused(ProgramConfig::PAUSE_DISTRIBUTIONS)?;
+
+    require!(
+        ctx.accounts.investor.key() == ctx.accounts.investment_record.investor,
+        ErrorCode::Unauthorized
+    );
+
+    ctx.accounts.investment_record.accrue_returns(&ctx.accounts.startup)?;
+    let amount = ctx.accounts.investment_record.unclaimed_returns;
+    require!(amount > 0, ErrorCode::NothingToClaim);
+
+    let escrow_bump = ctx.bumps.escrow_pda;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"escrow_authority", &[escrow_bump]]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.distribution_vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.investor_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_pda.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    let record = &mut ctx.accounts.investment_record;
+    record.unclaimed_returns = 0;
+    record.returns_claimed = record
+        .returns_claimed
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::Overflow))?;
+
+    emit!(ReturnsClaimed {
+        investor: record.investor,
+        startup_id: record.startup_id,
+        investment_record: record.key(),
+        amount,
+        returns_claimed: record.returns_claimed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
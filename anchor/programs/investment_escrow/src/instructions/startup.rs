@@ -0,0 +1,289 @@
+//! Startup registration and raise lifecycle
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022};
+
+use crate::errors::ErrorCode;
+use crate::events::*;
+use crate::state::*;
+
+/// Register a startup in the on-chain catalog (admin only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct RegisterStartup<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Startup::LEN,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump
+    )]
+    pub startup: Account<'info, Startup>,
+
+    /// Enabled allowlist entry for the mint the startup raises in
+    #[account(
+        seeds = [b"accepted_mint", accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(address = accepted_mint.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Share mint for the raise, with the stablecoin's decimals
+    /// The startup PDA mints shares, keeps holder accounts frozen and, as
+    /// permanent delegate, burns shares on exit without the holder's signature
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"share_mint", startup_id.as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = startup,
+        mint::freeze_authority = startup,
+        mint::token_program = share_token_program,
+        extensions::permanent_delegate::delegate = startup
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Share mints are always Token-2022 (permanent delegate extension)
+    pub share_token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open or close a registered startup (admin only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct UpdateStartupStatus<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+}
+
+/// Settle a raise as Funded or Failed once it has ended (permissionless)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct FinalizeRaise<'info> {
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+}
+
+/// Set a startup's secondary-market royalty (admin only)
+#[derive(Accounts)]
+#[instruction(startup_id: Pubkey)]
+pub struct SetStartupRoyalty<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"startup", startup_id.as_ref()],
+        bump = startup.bump
+    )]
+    pub startup: Account<'info, Startup>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn register_startup(
+    ctx: Context<RegisterStartup>,
+    startup_id: Pubkey,
+    name: String,
+    payout_wallet: Pubkey,
+    funding_target: u64,
+    hard_cap: u64,
+    opens_at: i64,
+    closes_at: i64,
+    term_length: i64,
+    expected_return: u8,
+    share_price: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(name.len() <= Startup::MAX_NAME_LEN, ErrorCode::NameTooLong);
+    require!(
+        hard_cap > 0 && funding_target <= hard_cap,
+        ErrorCode::InvalidFundingTarget
+    );
+    require!(opens_at < closes_at, ErrorCode::InvalidFundingWindow);
+    require!(term_length > 0, ErrorCode::InvalidTermLength);
+    require!(expected_return <= 100, ErrorCode::InvalidReturnPercentage);
+    require!(share_price > 0, ErrorCode::InvalidSharePrice);
+    require!(ctx.accounts.accepted_mint.enabled, ErrorCode::MintNotAccepted);
+
+    let startup = &mut ctx.accounts.startup;
+    startup.startup_id = startup_id;
+    startup.name = name;
+    startup.payout_wallet = payout_wallet;
+    startup.mint = ctx.accounts.accepted_mint.mint;
+    startup.funding_target = funding_target;
+    startup.hard_cap = hard_cap;
+    startup.total_raised = 0;
+    startup.total_released = 0;
+    startup.total_refunded = 0;
+    startup.opens_at = opens_at;
+    startup.closes_at = closes_at;
+    startup.status = StartupStatus::Open;
+    startup.release_proposal_count = 0;
+    startup.milestone_count = 0;
+    startup.milestone_bps_total = 0;
+    startup.share_mint = ctx.accounts.share_mint.key();
+    startup.share_price = share_price;
+    startup.active_shares = 0;
+    startup.royalty_bps = 0;
+    startup.reward_per_share = 0;
+    startup.total_distributed = 0;
+    startup.term_length = term_length;
+    startup.total_repaid = 0;
+    startup.total_shortfall = 0;
+    startup.bump = ctx.bumps.startup;
+    startup.version = ACCOUNT_VERSION;
+    startup.expected_return = expected_return;
+    startup.total_owed = 0;
+    startup.cancelled_escrow_balance = 0;
+    startup.cancelled_net_raised = 0;
+
+    emit!(StartupRegistered {
+        startup_id,
+        name: startup.name.clone(),
+        payout_wallet,
+        mint: startup.mint,
+        funding_target,
+        hard_cap,
+        opens_at,
+        closes_at,
+        term_length,
+        expected_return,
+        share_mint: startup.share_mint,
+        share_price,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn update_startup_status(
+    ctx: Context<UpdateStartupStatus>,
+    startup_id: Pubkey,
+    status: StartupStatus,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.startup.status != StartupStatus::Cancelled,
+        ErrorCode::StartupCancelled
+    );
+    ctx.accounts.startup.require_current()?;
+
+    // Funded and Failed are only reached through `finalize_raise`;
+    // a funded startup can still be cancelled
+    let old_status = ctx.accounts.startup.status;
+    let allowed = match old_status {
+        StartupStatus::Open | StartupStatus::Closed => matches!(
+            status,
+            StartupStatus::Open | StartupStatus::Closed | StartupStatus::Cancelled
+        ),
+        StartupStatus::Funded => status == StartupStatus::Cancelled,
+        _ => false,
+    };
+    require!(allowed, ErrorCode::InvalidStatusTransition);
+
+    let startup = &mut ctx.accounts.startup;
+    startup.status = status;
+    if status == StartupStatus::Cancelled {
+        // Refunds share what escrow holds now, so they don't depend on who refunds first
+        startup.cancelled_escrow_balance = startup.escrow_balance()?;
+        startup.cancelled_net_raised = startup.net_raised()?;
+    }
+
+    emit!(StartupStatusUpdated {
+        startup_id,
+        old_status,
+        new_status: status,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn finalize_raise(ctx: Context<FinalizeRaise>, startup_id: Pubkey) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let startup = &mut ctx.accounts.startup;
+
+    let ended = match startup.status {
+        StartupStatus::Open => now > startup.closes_at,
+        StartupStatus::Closed => true,
+        _ => false,
+    };
+    require!(ended, ErrorCode::RaiseNotEnded);
+
+    let net_raised = startup.net_raised()?;
+    let old_status = startup.status;
+    startup.status = if net_raised >= startup.funding_target {
+        StartupStatus::Funded
+    } else {
+        StartupStatus::Failed
+    };
+
+    emit!(RaiseFinalized {
+        startup_id,
+        old_status,
+        new_status: startup.status,
+        net_raised,
+        funding_target: startup.funding_target,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn set_startup_royalty(
+    ctx: Context<SetStartupRoyalty>,
+    startup_id: Pubkey,
+    royalty_bps: u16,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.config.admin,
+        ErrorCode::Unauthorized
+    );
+    require!(
+        royalty_bps <= ProgramConfig::MAX_FEE_BPS,
+        ErrorCode::InvalidFeeBps
+    );
+
+    let startup = &mut ctx.accounts.startup;
+    let old_royalty_bps = startup.royalty_bps;
+    startup.royalty_bps = royalty_bps;
+
+    emit!(StartupRoyaltyUpdated {
+        startup_id,
+        old_royalty_bps,
+        new_royalty_bps: royalty_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
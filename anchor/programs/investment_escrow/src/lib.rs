@@ -1,221 +1,115 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer, TokenAccount, Token, Mint};
 
 // ============================================================================
 // PROGRAM ID & CONFIGURATION
 // ============================================================================
 
-// Program ID (Placeholder - Replace after deployment)
+// Program ID per cluster, selected by exactly one of the `localnet` (default),
+// `devnet` or `mainnet` cargo features. build.rs checks the selected ID against
+// `[programs.<cluster>]` in Anchor.toml and warns while a live cluster's ID is
+// still the placeholder.
+#[cfg(feature = "localnet")]
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-// USDC Mint Address (Devnet: 4zMMC9srt5Ri1KseAPa9KUKFdgS2uK4JCT2TSXDKXrm)
-pub mod usdc_mint_key {
-    use anchor_lang::prelude::declare_id;
-    declare_id!("4zMMC9srt5Ri1KseAPa9KUKFdgS2uK4JCT2TSXDKXrm");
-}
-
-// ============================================================================
-// STATE ACCOUNTS
-// ============================================================================
-
-/// Investment Record - Immutable record of each investment transaction
-/// Stored in a Program Derived Address (PDA) for security and discoverability
-#[account]
-pub struct InvestmentAccount {
-    /// Investor's Phantom Wallet Public Key
-    pub investor: Pubkey,
-    /// Investment Asset's unique identifier
-    pub startup_id: Pubkey,
-    /// USDC amount invested (in lamports)
-    pub principal_usd: u64,
-    /// Investment transaction timestamp
-    pub investment_date: i64,
-    /// Expected return percentage (0-100)
-    pub expected_return: u8,
-    /// Investment status (0: Active, 1: Completed, 2: Cancelled)
-    pub status: u8,
-    /// PDA bump seed for address derivation
-    pub bump: u8,
-}
+// Placeholder - Replace after deployment
+#[cfg(feature = "devnet")]
+declare_id!("11111111111111111111111111111111");
+
+// Placeholder - Replace after deployment
+#[cfg(feature = "mainnet")]
+declare_id!("11111111111111111111111111111111");
+
+#[cfg(not(any(feature = "localnet", feature = "devnet", feature = "mainnet")))]
+compile_error!("enable one of the `localnet`, `devnet` or `mainnet` features");
+
+#[cfg(any(
+    all(feature = "localnet", feature = "devnet"),
+    all(feature = "localnet", feature = "mainnet"),
+    all(feature = "devnet", feature = "mainnet")
+))]
+compile_error!("the `localnet`, `devnet` and `mainnet` features are mutually exclusive");
+
+/// Stablecoin mints per cluster that `add_accepted_mint` may allowlist; the
+/// selected cluster's mints are re-exported here. Adding a stablecoin takes a
+/// program upgrade, so a mainnet build can never accept a test-cluster mint.
+pub mod cluster_mints {
+    use anchor_lang::prelude::*;
+
+    /// Local validator: test mints the TS suite creates at the committed
+    /// keypairs in tests/keys, with the provider wallet as mint authority
+    pub mod localnet {
+        use anchor_lang::prelude::*;
+
+        pub const USDC: Pubkey = pubkey!("75VTt9RpX3pUHqnFT6iPMhD8zbcGmc6yjsMektXv4bSZ");
+        /// Token-2022 stablecoin with a transfer fee, so the fee-adjusted
+        /// escrow accounting runs against a real mint
+        pub const FEE_USD: Pubkey = pubkey!("2BBJXrdyVxnxrQWBKrpU1VZMpHgZd7MqT4Dskyvmotwt");
+        pub const ALL: &[Pubkey] = &[USDC, FEE_USD];
+    }
 
-impl InvestmentAccount {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 1 + 1; // Discriminator + fields
-}
+    pub mod devnet {
+        use anchor_lang::prelude::*;
 
-/// Program Config - Admin settings for the investment program
-#[account]
-pub struct ProgramConfig {
-    /// Admin wallet with authority to manage settings
-    pub admin: Pubkey,
-    /// Minimum investment amount (in USDC lamports)
-    pub min_investment: u64,
-    /// Maximum investment amount per transaction
-    pub max_investment: u64,
-    /// Platform fee percentage (0-100, represents basis points)
-    pub platform_fee_bps: u16,
-    /// PDA bump
-    pub bump: u8,
-}
+        pub const USDC: Pubkey = pubkey!("4zMMC9srt5Ri1KseAPa9KUKFdgS2uK4JCT2TSXDKXrm");
+        pub const ALL: &[Pubkey] = &[USDC];
+    }
 
-impl ProgramConfig {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 2 + 1;
-}
+    /// PYUSD is left out: its Token-2022 mint has a permanent delegate, which
+    /// `AcceptedMint::check_extensions` refuses
+    pub mod mainnet {
+        use anchor_lang::prelude::*;
 
-/// Escrow State - Tracks pooled funds and state
-#[account]
-pub struct EscrowState {
-    /// Total USDC held in escrow
-    pub total_escrow: u64,
-    /// Total number of active investments
-    pub active_investments: u64,
-    /// Authority that can release funds
-    pub release_authority: Pubkey,
-    /// PDA bump
-    pub bump: u8,
-}
+        pub const USDC: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        pub const USDT: Pubkey = pubkey!("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+        pub const ALL: &[Pubkey] = &[USDC, USDT];
+    }
 
-impl EscrowState {
-    pub const LEN: usize = 8 + 8 + 8 + 32 + 1;
+    #[cfg(feature = "localnet")]
+    pub use localnet::*;
+    #[cfg(feature = "devnet")]
+    pub use devnet::*;
+    #[cfg(feature = "mainnet")]
+    pub use mainnet::*;
+
+    // A mainnet build must never ship a test-cluster mint
+    const _: () = assert!(
+        disjoint(mainnet::ALL, devnet::ALL) && disjoint(mainnet::ALL, localnet::ALL),
+        "mainnet default mints overlap a test cluster's mints"
+    );
+
+    const fn disjoint(a: &[Pubkey], b: &[Pubkey]) -> bool {
+        let mut i = 0;
+        while i < a.len() {
+            let mut j = 0;
+            while j < b.len() {
+                let (x, y) = (a[i].to_bytes(), b[j].to_bytes());
+                let mut k = 0;
+                while k < 32 && x[k] == y[k] {
+                    k += 1;
+                }
+                if k == 32 {
+                    return false;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        true
+    }
 }
 
-// ============================================================================
-// INSTRUCTION CONTEXTS
-// ============================================================================
-
-/// Initialize the program (admin only)
-#[derive(Accounts)]
-#[instruction(admin: Pubkey)]
-pub struct InitializeProgram<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-
-    #[account(
-        init,
-        payer = payer,
-        space = ProgramConfig::LEN,
-        seeds = [b"config"],
-        bump
-    )]
-    pub config: Account<'info, ProgramConfig>,
-
-    #[account(
-        init,
-        payer = payer,
-        space = EscrowState::LEN,
-        seeds = [b"escrow"],
-        bump
-    )]
-    pub escrow_state: Account<'info, EscrowState>,
-
-    pub system_program: Program<'info, System>,
-}
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod state;
 
-/// Investment context - Core feature for USDC transfers
-#[derive(Accounts)]
-#[instruction(amount: u64, startup_id: Pubkey)]
-pub struct InvestUSD<'info> {
-    /// The investor who signs and authorizes the transaction
-    #[account(mut)]
-    pub investor: Signer<'info>,
-
-    /// Investor's USDC Token Account (source of funds)
-    /// Must have sufficient balance for the investment
-    #[account(
-        mut,
-        token::mint = usdc_mint,
-        token::authority = investor
-    )]
-    pub investor_token_account: Account<'info, TokenAccount>,
-
-    /// Program's Escrow Token Account (destination)
-    /// Holds pooled USDC from all investors
-    #[account(
-        mut,
-        token::mint = usdc_mint,
-        token::authority = escrow_pda
-    )]
-    pub program_escrow_ata: Account<'info, TokenAccount>,
-
-    /// Escrow PDA authority (seeds: ["escrow_authority"])
-    /// Derived PDA that controls the escrow account
-    /// CHECK: This is a PDA, verified by seeds
-    #[account(
-        seeds = [b"escrow_authority"],
-        bump
-    )]
-    pub escrow_pda: UncheckedAccount<'info>,
-
-    /// Investment record PDA - Created here, stores immutable investment data
-    /// Unique per investor per startup (derived from seeds)
-    #[account(
-        init,
-        payer = investor,
-        space = InvestmentAccount::LEN,
-        seeds = [b"investment", investor.key().as_ref(), startup_id.as_ref()],
-        bump
-    )]
-    pub investment_record: Account<'info, InvestmentAccount>,
-
-    /// Program configuration account
-    #[account(seeds = [b"config"], bump)]
-    pub config: Account<'info, ProgramConfig>,
-
-    /// Escrow state account
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow_state: Account<'info, EscrowState>,
-
-    /// USDC Mint (SPL Token standard)
-    #[account(address = usdc_mint_key::ID)]
-    pub usdc_mint: Account<'info, Mint>,
-
-    /// SPL Token Program (for CPI calls)
-    pub token_program: Program<'info, Token>,
-
-    /// System Program (for account creation)
-    pub system_program: Program<'info, System>,
-
-    /// Clock Sysvar (for timestamps)
-    pub clock: Sysvar<'info, Clock>,
-}
+#[cfg(test)]
+mod tests;
 
-/// Release funds from escrow (admin/authority only)
-#[derive(Accounts)]
-#[instruction(startup_id: Pubkey)]
-pub struct ReleaseFunds<'info> {
-    #[account(mut)]
-    pub release_authority: Signer<'info>,
-
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow_state: Account<'info, EscrowState>,
-
-    #[account(seeds = [b"config"], bump)]
-    pub config: Account<'info, ProgramConfig>,
-
-    #[account(
-        mut,
-        token::mint = usdc_mint,
-        token::authority = escrow_pda
-    )]
-    pub escrow_ata: Account<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        token::mint = usdc_mint
-    )]
-    pub destination_ata: Account<'info, TokenAccount>,
-
-    /// CHECK: This is a PDA, verified by seeds
-    #[account(
-        seeds = [b"escrow_authority"],
-        bump
-    )]
-    pub escrow_pda: UncheckedAccount<'info>,
-
-    #[account(address = usdc_mint_key::ID)]
-    pub usdc_mint: Account<'info, Mint>,
-
-    pub token_program: Program<'info, Token>,
-}
+pub use errors::ErrorCode;
+pub use events::*;
+pub use instructions::*;
+pub use state::*;
 
 // ============================================================================
 // PROGRAM LOGIC
@@ -233,176 +127,489 @@ pub mod salonevest_program {
         min_investment: u64,
         max_investment: u64,
         platform_fee_bps: u16,
+        fee_mode: FeeMode,
+        cooling_off_period: i64,
     ) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.admin = admin;
-        config.min_investment = min_investment;
-        config.max_investment = max_investment;
-        config.platform_fee_bps = platform_fee_bps;
-        config.bump = ctx.bumps.get("config").copied().ok_or(error!(ErrorCode::BumpNotFound))?;
+        instructions::initialize_program(
+            ctx,
+            admin,
+            min_investment,
+            max_investment,
+            platform_fee_bps,
+            fee_mode,
+            cooling_off_period,
+        )
+    }
 
-        let escrow_state = &mut ctx.accounts.escrow_state;
-        escrow_state.total_escrow = 0;
-        escrow_state.active_investments = 0;
-        escrow_state.release_authority = admin;
-        escrow_state.bump = ctx.bumps.get("escrow_state").copied().ok_or(error!(ErrorCode::BumpNotFound))?;
+    /// Add a stablecoin to the accepted-mint allowlist
+    /// Admin-gated instruction; also creates the mint's escrow vault.
+    /// Only the build cluster's `cluster_mints` can be added, and Token-2022
+    /// mints with unsafe extensions (e.g. permanent delegate) are refused.
+    pub fn add_accepted_mint(ctx: Context<AddAcceptedMint>) -> Result<()> {
+        instructions::add_accepted_mint(ctx)
+    }
 
-        msg!("✅ SaloneVest Program initialized successfully");
-        msg!("   Admin: {}", admin);
-        msg!("   Min Investment: {} USDC", min_investment);
-        msg!("   Max Investment: {} USDC", max_investment);
-        msg!("   Platform Fee: {}%", platform_fee_bps as f32 / 100.0);
+    /// Enable or disable an accepted mint for new startups and investments
+    /// Admin-gated instruction; existing positions can always be released, refunded and settled
+    pub fn set_accepted_mint_enabled(
+        ctx: Context<SetAcceptedMintEnabled>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_accepted_mint_enabled(ctx, enabled)
+    }
 
-        Ok(())
+    /// Update investment limits, platform fee, cooling-off period, handover
+    /// expiry, release authority, compliance authority, pauser or holding period
+    /// Admin-gated instruction; `None` leaves a value unchanged.
+    /// A new release authority is only proposed: it takes over once it calls
+    /// `accept_release_authority`, as with `propose_release_authority`.
+    /// Emits `ConfigUpdated` with the old and new values.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        min_investment: Option<u64>,
+        max_investment: Option<u64>,
+        platform_fee_bps: Option<u16>,
+        fee_mode: Option<FeeMode>,
+        cooling_off_period: Option<i64>,
+        handover_ttl: Option<i64>,
+        release_authority: Option<Pubkey>,
+        compliance_authority: Option<Pubkey>,
+        pauser: Option<Pubkey>,
+        min_holding_period: Option<i64>,
+    ) -> Result<()> {
+        instructions::update_config(
+            ctx,
+            min_investment,
+            max_investment,
+            platform_fee_bps,
+            fee_mode,
+            cooling_off_period,
+            handover_ttl,
+            release_authority,
+            compliance_authority,
+            pauser,
+            min_holding_period,
+        )
+    }
+
+    /// Step 1 of an admin handover: propose a new admin
+    /// Admin-gated instruction; the proposal expires after `handover_ttl`
+    pub fn propose_admin(ctx: Context<HandoverAuthority>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    /// Step 2 of an admin handover: the proposed admin accepts
+    pub fn accept_admin(ctx: Context<HandoverAuthority>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    /// Withdraw a pending admin handover
+    /// Admin-gated instruction
+    pub fn cancel_admin_handover(ctx: Context<HandoverAuthority>) -> Result<()> {
+        instructions::cancel_admin_handover(ctx)
+    }
+
+    /// Step 1 of a release-authority handover: propose a new release authority
+    /// Callable by the admin or the current release authority
+    pub fn propose_release_authority(
+        ctx: Context<HandoverAuthority>,
+        new_release_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_release_authority(ctx, new_release_authority)
+    }
+
+    /// Step 2 of a release-authority handover: the proposed key accepts
+    pub fn accept_release_authority(ctx: Context<HandoverAuthority>) -> Result<()> {
+        instructions::accept_release_authority(ctx)
+    }
+
+    /// Withdraw a pending release-authority handover
+    /// Callable by the admin or the current release authority
+    pub fn cancel_release_authority_handover(ctx: Context<HandoverAuthority>) -> Result<()> {
+        instructions::cancel_release_authority_handover(ctx)
+    }
+
+    /// Halt the operations in `flags` (see `ProgramConfig::PAUSE_*`)
+    /// Pauser- or admin-gated instruction. Leave `PAUSE_REFUNDS` out of `flags`
+    /// to keep refunds available while everything else is halted.
+    pub fn pause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+        instructions::pause(ctx, flags)
+    }
+
+    /// Resume the operations in `flags`
+    /// Admin-gated instruction; the pauser can halt but not resume
+    pub fn unpause(ctx: Context<SetPause>, flags: u8) -> Result<()> {
+        instructions::unpause(ctx, flags)
+    }
+
+    /// Issue a KYC attestation for an investor wallet
+    /// Compliance-authority-gated instruction; only a hash of the off-chain KYC record is stored
+    pub fn issue_attestation(
+        ctx: Context<IssueAttestation>,
+        investor: Pubkey,
+        tier: u8,
+        jurisdiction: [u8; 2],
+        kyc_hash: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::issue_attestation(ctx, investor, tier, jurisdiction, kyc_hash, expires_at)
+    }
+
+    /// Renew an investor's attestation after re-verification
+    /// Compliance-authority-gated instruction; also lifts a previous revocation
+    pub fn renew_attestation(
+        ctx: Context<UpdateAttestation>,
+        investor: Pubkey,
+        tier: u8,
+        jurisdiction: [u8; 2],
+        kyc_hash: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::renew_attestation(ctx, investor, tier, jurisdiction, kyc_hash, expires_at)
+    }
+
+    /// Revoke an investor's attestation, blocking further investments
+    /// Compliance-authority-gated instruction; existing positions are unaffected
+    pub fn revoke_attestation(ctx: Context<UpdateAttestation>, investor: Pubkey) -> Result<()> {
+        instructions::revoke_attestation(ctx, investor)
+    }
+
+    /// Register a startup in the on-chain catalog
+    /// Admin-gated instruction; investments are only accepted into registered startups
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_startup(
+        ctx: Context<RegisterStartup>,
+        startup_id: Pubkey,
+        name: String,
+        payout_wallet: Pubkey,
+        funding_target: u64,
+        hard_cap: u64,
+        opens_at: i64,
+        closes_at: i64,
+        term_length: i64,
+        expected_return: u8,
+        share_price: u64,
+    ) -> Result<()> {
+        instructions::register_startup(
+            ctx,
+            startup_id,
+            name,
+            payout_wallet,
+            funding_target,
+            hard_cap,
+            opens_at,
+            closes_at,
+            term_length,
+            expected_return,
+            share_price,
+        )
+    }
+
+    /// Open, close or cancel a registered startup
+    /// Admin-gated instruction; cancelling is final and lets investors reclaim their principal,
+    /// pro-rata from what is left in escrow if part of a funded raise was released
+    pub fn update_startup_status(
+        ctx: Context<UpdateStartupStatus>,
+        startup_id: Pubkey,
+        status: StartupStatus,
+    ) -> Result<()> {
+        instructions::update_startup_status(ctx, startup_id, status)
+    }
+
+    /// Finalize a raise after its deadline (or once the admin has closed it early)
+    /// Permissionless: Funded if the net raise reached the soft cap, Failed otherwise.
+    pub fn finalize_raise(ctx: Context<FinalizeRaise>, startup_id: Pubkey) -> Result<()> {
+        instructions::finalize_raise(ctx, startup_id)
     }
 
     /// CORE FEATURE: invest_usd
     /// 
     /// Executes a USDC investment by:
     /// 1. Transferring USDC from investor to program escrow (Cross-Program Invocation)
-    /// 2. Creating an immutable investment record in a PDA
-    /// 3. Updating escrow state
+    ///    and, when fees are charged on invest, the platform fee to the treasury
+    /// 2. Minting startup shares for the principal at the raise's share price
+    /// 3. Creating an immutable investment record in a PDA
+    /// 4. Updating escrow state
     ///
     /// Security features:
     /// - Token amount validated against config limits
     /// - Investor wallet signature required
-    /// - USDC mint verified
-    /// - Investment record uniquely derived (prevents duplicates)
+    /// - Investor must hold a valid, unexpired KYC attestation
+    /// - Lifetime and rolling 12-month totals capped per attestation tier
+    /// - Mint verified against the startup's accepted mint
+    /// - Principal recorded net of any Token-2022 transfer fee
+    /// - Share account kept frozen; shares only move through the program
+    /// - Startup must be registered, open and below its hard cap
+    /// - Return owed at maturity is the startup's admin-set rate, not investor input
+    /// - Investment record uniquely derived per position (repeat investments open new positions)
     pub fn invest_usd(
         ctx: Context<InvestUSD>,
         amount: u64,
         startup_id: Pubkey,
-        expected_return: u8,
     ) -> Result<()> {
-        let config = &ctx.accounts.config;
-
-        // ===== VALIDATION =====
-        require!(amount >= config.min_investment, ErrorCode::AmountTooSmall);
-        require!(amount <= config.max_investment, ErrorCode::AmountTooLarge);
-        require!(expected_return <= 100, ErrorCode::InvalidReturnPercentage);
-
-        // ===== FEATURE 1: USDC TOKEN TRANSFER (CPI) =====
-        // This executes the payment in the same atomic transaction
-        // No fees are deducted by this contract (passed through as-is)
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.investor_token_account.to_account_info(),
-            to: ctx.accounts.program_escrow_ata.to_account_info(),
-            authority: ctx.accounts.investor.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-
-        // Execute the USDC transfer
-        token::transfer(cpi_ctx, amount)?;
-
-        // ===== FEATURE 2: IMMUTABLE INVESTMENT RECORD =====
-        // Store the transaction details in a PDA for permanent record
-        let investment_record = &mut ctx.accounts.investment_record;
-        investment_record.investor = ctx.accounts.investor.key();
-        investment_record.startup_id = startup_id;
-        investment_record.principal_usd = amount;
-        investment_record.investment_date = ctx.accounts.clock.unix_timestamp;
-        investment_record.expected_return = expected_return;
-        investment_record.status = 0; // 0 = Active
-        investment_record.bump = ctx.bumps.get("investment_record").copied().ok_or(error!(ErrorCode::BumpNotFound))?;
-
-        // ===== UPDATE ESCROW STATE =====
-        let escrow_state = &mut ctx.accounts.escrow_state;
-        escrow_state.total_escrow = escrow_state
-            .total_escrow
-            .checked_add(amount)
-            .ok_or(error!(ErrorCode::Overflow))?;
-        escrow_state.active_investments = escrow_state
-            .active_investments
-            .checked_add(1)
-            .ok_or(error!(ErrorCode::Overflow))?;
-
-        // ===== LOGGING & EVENTS =====
-        msg!("✅ SaloneVest Investment Recorded");
-        msg!("   Investor: {}", ctx.accounts.investor.key());
-        msg!("   Amount: {} USDC", amount);
-        msg!("   Startup: {}", startup_id);
-        msg!("   Expected Return: {}%", expected_return);
-        msg!("   Timestamp: {}", ctx.accounts.clock.unix_timestamp);
-        msg!("   Total Escrow: {} USDC", escrow_state.total_escrow);
-
-        Ok(())
+        instructions::invest_usd(ctx, amount, startup_id)
     }
 
     /// Release funds from escrow to a startup wallet
-    /// Authority-gated instruction (only admin/release_authority can call)
+    /// Executes a release proposal once M of N approvers have approved it;
+    /// each proposal pays out the tranche of one approved milestone
+    /// Only the USDC raised for `startup_id` can be released to that startup
+    /// When fees are charged on release, the platform fee is sent to the treasury
     pub fn release_funds(
         ctx: Context<ReleaseFunds>,
         startup_id: Pubkey,
+    ) -> Result<()> {
+        instructions::release_funds(ctx, startup_id)
+    }
+
+    /// Create the next milestone of a startup
+    /// Admin-gated instruction; milestones may not add up to more than 100% of the raise
+    pub fn create_milestone(
+        ctx: Context<CreateMilestone>,
+        startup_id: Pubkey,
+        description_hash: [u8; 32],
+        percent_bps: u16,
+        due_date: i64,
+    ) -> Result<()> {
+        instructions::create_milestone(ctx, startup_id, description_hash, percent_bps, due_date)
+    }
+
+    /// Mark a pending milestone as reached, unlocking its tranche
+    /// Release-authority-gated instruction
+    pub fn approve_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+        instructions::approve_milestone(ctx)
+    }
+
+    /// Reject a pending milestone; its tranche can never be released, and its
+    /// share of the raise is freed for new milestones
+    /// Release-authority-gated instruction
+    pub fn reject_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+        instructions::reject_milestone(ctx)
+    }
+
+    /// Replace the release approver set and approval threshold
+    /// Admin-gated instruction
+    pub fn set_release_approvers(
+        ctx: Context<SetReleaseApprovers>,
+        approvers: Vec<Pubkey>,
+        approval_threshold: u8,
+        release_proposal_ttl: i64,
+    ) -> Result<()> {
+        instructions::set_release_approvers(
+            ctx,
+            approvers,
+            approval_threshold,
+            release_proposal_ttl,
+        )
+    }
+
+    /// Replace the per-tier investment caps
+    /// Admin-gated instruction; tier N uses `tier_limits[N - 1]`, unlisted tiers cannot invest
+    pub fn set_tier_limits(ctx: Context<SetTierLimits>, tier_limits: Vec<TierLimit>) -> Result<()> {
+        instructions::set_tier_limits(ctx, tier_limits)
+    }
+
+    /// Propose releasing an approved milestone's tranche to the startup's payout account
+    /// Approver-gated instruction; the proposer's approval is recorded immediately.
+    /// The raise must be funded so the tranche is a fixed share of the final amount.
+    pub fn propose_release(
+        ctx: Context<ProposeRelease>,
+        startup_id: Pubkey,
+        milestone_index: u8,
+    ) -> Result<()> {
+        instructions::propose_release(ctx, startup_id, milestone_index)
+    }
+
+    /// Approve a pending release proposal
+    /// Approver-gated instruction
+    pub fn approve_release(ctx: Context<ReviewRelease>) -> Result<()> {
+        instructions::approve_release(ctx)
+    }
+
+    /// Cancel a pending release proposal
+    /// Callable by the proposer, the admin or the release authority
+    pub fn cancel_release(ctx: Context<ReviewRelease>) -> Result<()> {
+        instructions::cancel_release(ctx)
+    }
+
+    /// Cancel an active investment and return its principal to the investor
+    /// Allowed for the investor inside the cooling-off window while the raise is
+    /// still open or closed (not yet finalized), or for the investor/admin once
+    /// the admin has cancelled the whole startup.
+    /// Platform fees already charged on invest are not refunded.
+    pub fn cancel_investment(ctx: Context<CancelInvestment>) -> Result<()> {
+        instructions::cancel_investment(ctx)
+    }
+
+    /// Refund an active investment in a failed raise
+    /// Permissionless: any signer may trigger it, the principal always goes to the investor.
+    pub fn refund_investment(ctx: Context<CancelInvestment>) -> Result<()> {
+        instructions::refund_investment(ctx)
+    }
+
+    /// Transfer an active position to another wallet (a gift, or a move off a lost device)
+    /// The position is re-created under the receiver's profile with its original
+    /// investment date, terms and unclaimed returns; the old record is kept as
+    /// Transferred. Only the owner signs and pays for the receiver's accounts; the
+    /// receiver must hold a valid attestation. Shares move with the position, so
+    /// startup distribution totals are unchanged.
+    pub fn transfer_position(ctx: Context<TransferPosition>) -> Result<()> {
+        instructions::transfer_position(ctx)
+    }
+
+    /// Set the royalty a startup receives on secondary-market fills
+    /// Admin-gated instruction
+    pub fn set_startup_royalty(
+        ctx: Context<SetStartupRoyalty>,
+        startup_id: Pubkey,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        instructions::set_startup_royalty(ctx, startup_id, royalty_bps)
+    }
+
+    /// List some or all of an active position's shares at an ask per whole share
+    /// Shares stay in the seller's account until a buyer fills the listing
+    pub fn list_position(
+        ctx: Context<ListPosition>,
+        shares: u64,
+        price_per_share: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::list_position(ctx, shares, price_per_share, expires_at)
+    }
+
+    /// Withdraw a listing and return its rent to the seller
+    /// The seller may cancel at any time; anyone may clear an expired listing.
+    pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+        instructions::cancel_listing(ctx)
+    }
+
+    /// Buy `shares` from a listing in one atomic swap
+    /// The buyer pays the ask: the startup royalty and platform fee come out of
+    /// it and the rest goes to the seller. The shares leave the seller's position
+    /// and become a new position for the buyer with the original investment date,
+    /// terms and a proportional part of the principal. The startup must be Open,
+    /// Closed or Funded, the buyer must hold a valid attestation, and the cost
+    /// counts toward the buyer's tier caps.
+    pub fn fill_listing(
+        ctx: Context<FillListing>,
+        shares: u64,
+        max_price_per_share: u64,
+    ) -> Result<()> {
+        instructions::fill_listing(ctx, shares, max_price_per_share)
+    }
+
+    /// Deposit repayments or revenue share into a startup's distribution vault
+    /// Callable by the startup's payout wallet or the admin.
+    /// Raises the startup's reward-per-share index in O(1), independent of investor count;
+    /// each share base unit held by an active position earns the same amount.
+    pub fn deposit_returns(
+        ctx: Context<DepositReturns>,
+        startup_id: Pubkey,
         amount: u64,
     ) -> Result<()> {
-        require!(
-            ctx.accounts.release_authority.key() == ctx.accounts.escrow_state.release_authority,
-            ErrorCode::Unauthorized
-        );
-
-        require!(
-            ctx.accounts.escrow_ata.amount >= amount,
-            ErrorCode::InsufficientFunds
-        );
-
-        // Create a signer bump for the escrow PDA
-        let escrow_bump = ctx.bumps.get("escrow_pda").copied().ok_or(error!(ErrorCode::BumpNotFound))?;
-        let signer_seeds: &[&[&[u8]]] = &[&[b"escrow_authority", &[escrow_bump]]];
-
-        // Transfer USDC from escrow to destination
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow_ata.to_account_info(),
-            to: ctx.accounts.destination_ata.to_account_info(),
-            authority: ctx.accounts.escrow_pda.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-        token::transfer(cpi_ctx, amount)?;
-
-        // Update escrow state
-        ctx.accounts.escrow_state.total_escrow = ctx.accounts.escrow_state
-            .total_escrow
-            .checked_sub(amount)
-            .ok_or(error!(ErrorCode::Underflow))?;
-
-        msg!("✅ Funds Released");
-        msg!("   Startup: {}", startup_id);
-        msg!("   Amount: {} USDC", amount);
-        msg!("   Remaining Escrow: {} USDC", ctx.accounts.escrow_state.total_escrow);
-
-        Ok(())
+        instructions::deposit_returns(ctx, startup_id, amount)
     }
-}
 
-// ============================================================================
-// ERROR CODES
-// ============================================================================
+    /// Deposit principal repayments into a startup's repayment vault
+    /// Callable by the startup's payout wallet or the admin; funds matured settlements.
+    pub fn deposit_repayment(
+        ctx: Context<DepositRepayment>,
+        startup_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_repayment(ctx, startup_id, amount)
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Investment amount is below minimum")]
-    AmountTooSmall,
+    /// Settle a matured investment from the startup's repayment vault
+    /// Every position is paid pro-rata: its amount due (principal plus the
+    /// startup's expected return) times total repaid / total owed, so the order
+    /// in which positions settle does not matter. The first settlement burns the
+    /// position's shares and marks it Completed, or Defaulted if repayments do
+    /// not cover it yet; a defaulted position is settled again as further
+    /// repayments arrive, until it is Completed.
+    pub fn settle_investment(ctx: Context<SettleInvestment>) -> Result<()> {
+        instructions::settle_investment(ctx)
+    }
 
-    #[msg("Investment amount exceeds maximum")]
-    AmountTooLarge,
+    /// Claim the returns an investment has earned from its startup's distributions
+    /// Pays shares x (reward-per-share growth since the last claim)
+    pub fn claim_returns(ctx: Context<ClaimReturns>) -> Result<()> {
+        instructions::claim_returns(ctx)
+    }
 
-    #[msg("Invalid return percentage (must be 0-100)")]
-    InvalidReturnPercentage,
+    /// Close a terminal investment record and return its rent to whoever paid it
+    /// (the investor, or the previous holder for a position received by transfer)
+    /// Emits a summary of the position first, so the history can be rebuilt from events.
+    /// Unclaimed returns must be claimed before closing.
+    pub fn close_investment(ctx: Context<CloseInvestment>) -> Result<()> {
+        instructions::close_investment(ctx)
+    }
 
-    #[msg("Insufficient funds in escrow")]
-    InsufficientFunds,
+    /// Sweep collected platform fees from the treasury
+    /// Admin-gated instruction
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_fees(ctx, amount)
+    }
 
-    #[msg("Unauthorized access")]
-    Unauthorized,
+    /// Upgrade the program config in place from v1 or v2
+    /// Admin-gated instruction; run before any other migration
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config(ctx)
+    }
 
-    #[msg("Arithmetic overflow")]
-    Overflow,
+    /// Upgrade the escrow state in place from v1 or v2
+    /// Admin-gated instruction
+    pub fn migrate_escrow_state(ctx: Context<MigrateAdminAccount>) -> Result<()> {
+        instructions::migrate_escrow_state(ctx)
+    }
 
-    #[msg("Arithmetic underflow")]
-    Underflow,
+    /// Upgrade a v2 accepted-mint entry in place
+    /// Admin-gated instruction
+    pub fn migrate_accepted_mint(ctx: Context<MigrateAdminAccount>) -> Result<()> {
+        instructions::migrate_accepted_mint(ctx)
+    }
+
+    /// Upgrade a v2 startup in place, backfilling the fields v3 added
+    /// (see `Startup::backfill_v2`)
+    /// Admin-gated instruction
+    pub fn migrate_startup(
+        ctx: Context<MigrateAdminAccount>,
+        expected_return: u8,
+        total_owed: u64,
+    ) -> Result<()> {
+        instructions::migrate_startup(ctx, expected_return, total_owed)
+    }
+
+    /// Upgrade a v2 milestone in place (permissionless)
+    pub fn migrate_milestone(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_milestone(ctx)
+    }
+
+    /// Upgrade a v2 release proposal in place (permissionless)
+    pub fn migrate_release_proposal(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_release_proposal(ctx)
+    }
+
+    /// Upgrade an investment record in place from v1 or v2 (permissionless)
+    /// A v2 record's rent is taken to have been paid by the investor.
+    pub fn migrate_investment(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_investment(ctx)
+    }
+
+    /// Upgrade a v2 investor profile in place (permissionless)
+    pub fn migrate_investor_profile(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_investor_profile(ctx)
+    }
+
+    /// Upgrade a v2 investor attestation in place (permissionless)
+    pub fn migrate_attestation(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_attestation(ctx)
+    }
 
-    #[msg("Bump not found in context")]
-    BumpNotFound,
+    /// Upgrade a v2 listing in place (permissionless)
+    pub fn migrate_listing(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_listing(ctx)
+    }
 }
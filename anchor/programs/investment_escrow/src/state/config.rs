@@ -0,0 +1,174 @@
+//! Program-wide configuration
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::{RESERVED_LEN, VERSIONING_LEN};
+
+/// Program Config - Admin settings for the investment program
+#[account]
+pub struct ProgramConfig {
+    /// Admin wallet with authority to manage settings
+    pub admin: Pubkey,
+    /// Minimum investment amount (in USDC lamports)
+    pub min_investment: u64,
+    /// Maximum investment amount per transaction
+    pub max_investment: u64,
+    /// Platform fee percentage (0-100, represents basis points)
+    pub platform_fee_bps: u16,
+    /// When the platform fee is charged
+    pub fee_mode: FeeMode,
+    /// Seconds after investing during which an investor may cancel
+    pub cooling_off_period: i64,
+    /// Seconds an admin/release-authority handover proposal stays valid
+    pub handover_ttl: i64,
+    /// Proposed new admin awaiting acceptance
+    pub pending_admin: Option<Pubkey>,
+    /// Timestamp after which the pending admin proposal expires
+    pub pending_admin_expires_at: i64,
+    /// Approver set for release proposals (N)
+    pub release_approvers: Vec<Pubkey>,
+    /// Approvals required before a release proposal can execute (M)
+    pub approval_threshold: u8,
+    /// Seconds a release proposal stays open for approval and execution
+    pub release_proposal_ttl: i64,
+    /// Issues, renews and revokes investor KYC attestations
+    pub compliance_authority: Pubkey,
+    /// Per-investor caps by attestation tier (index 0 = tier 1)
+    pub tier_limits: Vec<TierLimit>,
+    /// May halt operations in an emergency (the admin may too)
+    pub pauser: Pubkey,
+    /// Bitflags of halted operations (see `PAUSE_*`)
+    pub paused: u8,
+    /// Seconds a position must be held before it can be transferred (0 = no minimum)
+    pub min_holding_period: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl ProgramConfig {
+    pub const MAX_RELEASE_APPROVERS: usize = 10;
+    pub const MAX_TIERS: usize = 5;
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 2 + 1 + 8 + 8 + (1 + 32) + 8
+        + (4 + 32 * Self::MAX_RELEASE_APPROVERS) + 1 + 8 + 32
+        + (4 + TierLimit::LEN * Self::MAX_TIERS) + 32 + 1 + 8 + 1 + VERSIONING_LEN;
+
+    /// `invest_usd`
+    pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+    /// `release_funds`
+    pub const PAUSE_RELEASES: u8 = 1 << 1;
+    /// `cancel_investment` and `refund_investment`
+    pub const PAUSE_REFUNDS: u8 = 1 << 2;
+    /// Return distributions, repayments and settlements
+    pub const PAUSE_DISTRIBUTIONS: u8 = 1 << 3;
+    /// `transfer_position` and secondary-market fills
+    pub const PAUSE_TRANSFERS: u8 = 1 << 4;
+    pub const PAUSE_ALL: u8 = Self::PAUSE_DEPOSITS
+        | Self::PAUSE_RELEASES
+        | Self::PAUSE_REFUNDS
+        | Self::PAUSE_DISTRIBUTIONS
+        | Self::PAUSE_TRANSFERS;
+
+    /// Maximum platform fee (100%, in basis points)
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    /// Default lifetime of a handover proposal (7 days)
+    pub const DEFAULT_HANDOVER_TTL: i64 = 7 * 24 * 60 * 60;
+
+    /// Default lifetime of a release proposal (3 days)
+    pub const DEFAULT_RELEASE_PROPOSAL_TTL: i64 = 3 * 24 * 60 * 60;
+
+    /// Check the invariants every config write must uphold
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_investment <= self.max_investment,
+            ErrorCode::InvalidInvestmentLimits
+        );
+        require!(
+            self.platform_fee_bps <= Self::MAX_FEE_BPS,
+            ErrorCode::InvalidFeeBps
+        );
+        require!(self.cooling_off_period >= 0, ErrorCode::InvalidCoolingOffPeriod);
+        require!(self.min_holding_period >= 0, ErrorCode::InvalidHoldingPeriod);
+        require!(self.handover_ttl > 0, ErrorCode::InvalidHandoverTtl);
+        require!(
+            self.release_approvers.len() <= Self::MAX_RELEASE_APPROVERS
+                && self.approval_threshold > 0
+                && self.approval_threshold as usize <= self.release_approvers.len()
+                && self.release_proposal_ttl > 0,
+            ErrorCode::InvalidApproverSet
+        );
+        for (i, approver) in self.release_approvers.iter().enumerate() {
+            require!(
+                !self.release_approvers[..i].contains(approver),
+                ErrorCode::InvalidApproverSet
+            );
+        }
+        require!(
+            self.tier_limits.len() <= Self::MAX_TIERS
+                && self.tier_limits.iter().all(|limit| limit.annual_cap <= limit.lifetime_cap),
+            ErrorCode::InvalidTierLimits
+        );
+        Ok(())
+    }
+
+    /// Caps for an attestation tier (tiers start at 1)
+    pub fn tier_limit(&self, tier: u8) -> Result<TierLimit> {
+        tier.checked_sub(1)
+            .and_then(|index| self.tier_limits.get(index as usize))
+            .copied()
+            .ok_or(error!(ErrorCode::TierNotConfigured))
+    }
+
+    /// Fail if any operation in `flags` is paused
+    pub fn require_not_paused(&self, flags: u8) -> Result<()> {
+        require!(self.paused & flags == 0, ErrorCode::ProgramPaused);
+        Ok(())
+    }
+
+    pub fn is_release_approver(&self, key: &Pubkey) -> bool {
+        self.release_approvers.contains(key)
+    }
+
+    /// Platform fee owed on `amount` at the configured rate
+    pub fn platform_fee(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.platform_fee_bps as u128)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / 10_000;
+        u64::try_from(fee).map_err(|_| error!(ErrorCode::Overflow))
+    }
+}
+
+/// Investment caps for one compliance tier (e.g. retail vs sophisticated)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TierLimit {
+    /// Maximum an investor may ever invest (in USDC lamports)
+    pub lifetime_cap: u64,
+    /// Maximum an investor may invest over the rolling 12-month window
+    pub annual_cap: u64,
+}
+
+impl TierLimit {
+    pub const LEN: usize = 8 + 8;
+}
+
+/// Point at which the platform fee is split off
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeMode {
+    /// Deducted from each investment before it reaches escrow
+    OnInvest,
+    /// Deducted from each release before it reaches the startup
+    OnRelease,
+}
+
+/// Privileged role that can be handed over in two steps
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuthorityRole {
+    Admin,
+    ReleaseAuthority,
+}
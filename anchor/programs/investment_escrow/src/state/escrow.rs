@@ -0,0 +1,93 @@
+//! Escrow bookkeeping and the accepted-mint allowlist
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022;
+use anchor_spl::token_interface::spl_token_2022::extension::{
+    permanent_delegate::PermanentDelegate, transfer_hook::TransferHook, BaseStateWithExtensions,
+    ExtensionType, StateWithExtensions,
+};
+
+use crate::errors::ErrorCode;
+use super::{RESERVED_LEN, VERSIONING_LEN};
+
+/// Escrow State - Tracks program-wide escrow state
+/// Per-mint totals live in each `AcceptedMint`
+#[account]
+pub struct EscrowState {
+    /// Total number of active investments
+    pub active_investments: u64,
+    /// Authority that oversees releases (may cancel any release proposal)
+    pub release_authority: Pubkey,
+    /// Proposed new release authority awaiting acceptance
+    pub pending_release_authority: Option<Pubkey>,
+    /// Timestamp after which the pending release authority proposal expires
+    pub pending_release_authority_expires_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl EscrowState {
+    pub const LEN: usize = 8 + 8 + 32 + (1 + 32) + 8 + 1 + VERSIONING_LEN;
+}
+
+/// Accepted Mint - Allowlist entry for a stablecoin investors may use
+/// (seeds: ["accepted_mint", mint]); each mint has its own escrow vault,
+/// the escrow PDA's associated token account. SPL Token and Token-2022 mints are supported.
+#[account]
+pub struct AcceptedMint {
+    /// Stablecoin mint (e.g. USDC, USDT)
+    pub mint: Pubkey,
+    /// Whether new startups and investments may use this mint
+    /// Disabling never blocks releases, refunds or distributions
+    pub enabled: bool,
+    /// Total held in this mint's escrow vault
+    pub total_escrow: u64,
+    /// Total platform fees in this mint sent to the treasury
+    pub total_fees_collected: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl AcceptedMint {
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 1 + VERSIONING_LEN;
+
+    /// Token-2022 extensions that freeze or trap escrowed funds however they are configured
+    pub const UNSAFE_EXTENSIONS: [ExtensionType; 2] = [
+        ExtensionType::NonTransferable,
+        ExtensionType::DefaultAccountState,
+    ];
+
+    /// Refuse mints whose extensions make escrow unsafe: a permanent delegate
+    /// (can move escrowed funds), a transfer hook program (can block escrow
+    /// transfers) or any of `UNSAFE_EXTENSIONS`. A permanent-delegate or
+    /// transfer-hook extension with no delegate or program set is inert and
+    /// passes (e.g. a hook reserved for later use).
+    /// Legacy SPL Token mints carry no extensions and always pass
+    pub fn check_extensions(mint: &AccountInfo) -> Result<()> {
+        let data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        for extension in mint_state.get_extension_types()? {
+            let safe = match extension {
+                ExtensionType::PermanentDelegate => {
+                    let delegate = mint_state.get_extension::<PermanentDelegate>()?.delegate;
+                    Option::<Pubkey>::from(delegate).is_none()
+                }
+                ExtensionType::TransferHook => {
+                    let program_id = mint_state.get_extension::<TransferHook>()?.program_id;
+                    Option::<Pubkey>::from(program_id).is_none()
+                }
+                extension => !Self::UNSAFE_EXTENSIONS.contains(&extension),
+            };
+            require!(safe, ErrorCode::UnsupportedMintExtension);
+        }
+        Ok(())
+    }
+}
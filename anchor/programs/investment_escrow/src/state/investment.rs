@@ -0,0 +1,156 @@
+//! Investment records and their lifecycle
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::*;
+
+/// Investment Record - Immutable record of each investment transaction
+/// Stored in a Program Derived Address (PDA) for security and discoverability
+#[account]
+pub struct InvestmentAccount {
+    /// Investor's Phantom Wallet Public Key
+    pub investor: Pubkey,
+    /// Investment Asset's unique identifier
+    pub startup_id: Pubkey,
+    /// Stablecoin mint the investment was made in
+    pub mint: Pubkey,
+    /// Position number within the investor's profile (part of the PDA seeds)
+    pub position_index: u64,
+    /// USDC amount sent by the investor, before platform fees (in lamports)
+    pub gross_usd: u64,
+    /// USDC amount invested after platform fees (in lamports)
+    pub principal_usd: u64,
+    /// Startup shares minted for this position (in share mint base units)
+    pub shares: u64,
+    /// Investment transaction timestamp
+    pub investment_date: i64,
+    /// Return owed at maturity in percent (0-100), the startup's rate at investment
+    pub expected_return: u8,
+    /// Investment status; changed only through `transition`
+    pub status: InvestmentStatus,
+    /// Startup returns already accounted for (shares x reward-per-share at last checkpoint)
+    pub reward_debt: u64,
+    /// Returns accrued but not yet claimed
+    pub unclaimed_returns: u64,
+    /// Returns paid out to the investor so far
+    pub returns_claimed: u64,
+    /// USDC paid out from the repayment vault on settlement so far
+    pub settled_amount: u64,
+    /// Part of the amount due that repayments have not covered yet
+    pub shortfall: u64,
+    /// PDA bump seed for address derivation
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Wallet that paid the record's rent and is refunded when it is closed
+    pub rent_payer: Pubkey,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN - 32],
+}
+
+impl InvestmentAccount {
+    // Fields after `version` were carved out of the reserved space
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + VERSIONING_LEN; // Discriminator + fields
+
+    /// Timestamp from which the investment can be settled
+    pub fn maturity_date(&self, term_length: i64) -> Result<i64> {
+        self.investment_date
+            .checked_add(term_length)
+            .ok_or(error!(ErrorCode::Overflow))
+    }
+
+    /// Principal plus the expected return owed at maturity
+    pub fn amount_due(&self) -> Result<u64> {
+        let realised_return = (self.principal_usd as u128)
+            .checked_mul(self.expected_return as u128)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / 100;
+        u64::try_from(realised_return)
+            .ok()
+            .and_then(|realised_return| self.principal_usd.checked_add(realised_return))
+            .ok_or(error!(ErrorCode::Overflow))
+    }
+
+    /// Fail unless the record may be closed: terminal, with every return claimed
+    pub fn require_closable(&self) -> Result<()> {
+        require!(self.status.is_terminal(), ErrorCode::InvestmentNotTerminal);
+        require!(self.unclaimed_returns == 0, ErrorCode::UnclaimedReturnsPending);
+        Ok(())
+    }
+
+    /// Wallet the rent goes back to on close; records created before
+    /// `rent_payer` was tracked were paid for by the investor
+    pub fn rent_recipient(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.investor
+        } else {
+            self.rent_payer
+        }
+    }
+
+    /// Move the position to `next`; every status change goes through here
+    pub fn transition(&mut self, next: InvestmentStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(next),
+            ErrorCode::InvalidInvestmentTransition
+        );
+        self.status = next;
+        Ok(())
+    }
+
+    /// Checkpoint returns distributed since the last accrual into `unclaimed_returns`
+    /// Only active positions share in distributions
+    pub fn accrue_returns(&mut self, startup: &Startup) -> Result<()> {
+        if self.status != InvestmentStatus::Active {
+            return Ok(());
+        }
+        let accumulated = startup.accumulated_returns(self.shares)?;
+        let pending = accumulated
+            .checked_sub(self.reward_debt)
+            .ok_or(error!(ErrorCode::Underflow))?;
+        self.unclaimed_returns = self
+            .unclaimed_returns
+            .checked_add(pending)
+            .ok_or(error!(ErrorCode::Overflow))?;
+        self.reward_debt = accumulated;
+        Ok(())
+    }
+}
+
+/// Investment position lifecycle
+/// Variant order matches the former `u8` codes, so stored records decode unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InvestmentStatus {
+    /// Holds shares and earns distributions
+    Active,
+    /// Settled in full at maturity
+    Completed,
+    /// Cancelled by the investor or admin; principal returned
+    Cancelled,
+    /// Principal refunded after a failed raise
+    Refunded,
+    /// Re-homed to another wallet or fully sold on the secondary market
+    Transferred,
+    /// Settled at maturity with a shortfall; later repayments can still complete it
+    Defaulted,
+}
+
+impl InvestmentStatus {
+    /// Allowed status changes: an active position may move to any other
+    /// status, a defaulted one completes once repayments cover it, and every
+    /// other status is final
+    pub fn can_transition_to(self, next: InvestmentStatus) -> bool {
+        use InvestmentStatus::*;
+        matches!(
+            (self, next),
+            (Active, Completed | Cancelled | Refunded | Transferred | Defaulted)
+                | (Defaulted, Completed)
+        )
+    }
+
+    /// Final statuses; a record in one may be closed
+    pub fn is_terminal(self) -> bool {
+        !matches!(self, InvestmentStatus::Active | InvestmentStatus::Defaulted)
+    }
+}
@@ -0,0 +1,153 @@
+//! Per-investor totals and compliance attestations
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::*;
+
+/// Investor Profile - Per-investor position counter and investment totals
+/// Each investment (including repeat top-ups into the same startup) opens a new
+/// position whose record PDA is derived from the counter value at the time
+#[account]
+pub struct InvestorProfile {
+    /// Investor's wallet
+    pub investor: Pubkey,
+    /// Number of positions opened so far (next position index)
+    pub investment_count: u64,
+    /// Total USDC ever invested (gross, refunds do not restore headroom)
+    pub lifetime_invested: u64,
+    /// Ring buffer of USDC invested per period (1/12 of a year) over the last 12 periods
+    pub monthly_invested: [u64; 12],
+    /// Period (unix time / `PERIOD_SECONDS`) of the latest ring buffer slot
+    pub current_period: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl InvestorProfile {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 * Self::PERIODS + 8 + 1 + VERSIONING_LEN;
+
+    /// Length of one rolling-window bucket: 1/12 of a 365-day year (30.4 days),
+    /// so 12 buckets span a full year rather than 360 days. Buckets are fixed
+    /// slices of unix time, not calendar months, and the window advances a whole
+    /// bucket at a time: the annual cap covers between 11 and 12 buckets
+    /// (roughly 335 to 365 days) of history.
+    pub const PERIOD_SECONDS: i64 = 365 * 24 * 60 * 60 / Self::PERIODS as i64;
+    /// Buckets in the rolling 12-month window
+    pub const PERIODS: usize = 12;
+
+    /// Advance the ring buffer to `now`, clearing buckets that fell out of the window
+    fn roll(&mut self, now: i64) {
+        let period = now.div_euclid(Self::PERIOD_SECONDS);
+        if period <= self.current_period {
+            return;
+        }
+        let elapsed = (period - self.current_period).min(Self::PERIODS as i64);
+        for offset in 1..=elapsed {
+            let slot = (self.current_period + offset).rem_euclid(Self::PERIODS as i64);
+            self.monthly_invested[slot as usize] = 0;
+        }
+        self.current_period = period;
+    }
+
+    /// USDC invested over the rolling 12-month window
+    pub fn annual_invested(&self) -> Result<u64> {
+        self.monthly_invested
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(error!(ErrorCode::Overflow))
+    }
+
+    /// Add `amount` to the lifetime and rolling totals, enforcing the tier's caps
+    pub fn record_investment(&mut self, amount: u64, now: i64, limit: &TierLimit) -> Result<()> {
+        self.roll(now);
+
+        let lifetime_invested = self
+            .lifetime_invested
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::Overflow))?;
+        require!(
+            lifetime_invested <= limit.lifetime_cap,
+            ErrorCode::LifetimeCapExceeded
+        );
+        let annual_invested = self
+            .annual_invested()?
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::Overflow))?;
+        require!(annual_invested <= limit.annual_cap, ErrorCode::AnnualCapExceeded);
+
+        let slot = self.current_period.rem_euclid(Self::PERIODS as i64) as usize;
+        self.monthly_invested[slot] = self.monthly_invested[slot]
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::Overflow))?;
+        self.lifetime_invested = lifetime_invested;
+        Ok(())
+    }
+}
+
+/// Investor Attestation - Proof that a wallet passed off-chain KYC
+/// Issued by the compliance authority; holds only a hash of the KYC record, no personal data
+#[account]
+pub struct InvestorAttestation {
+    /// Attested investor wallet
+    pub investor: Pubkey,
+    /// Verification tier granted by the compliance provider
+    pub tier: u8,
+    /// ISO 3166-1 alpha-2 country code of the investor's jurisdiction (e.g. b"GB")
+    pub jurisdiction: [u8; 2],
+    /// Hash of the off-chain KYC record
+    pub kyc_hash: [u8; 32],
+    /// Compliance authority that last issued or renewed the attestation
+    pub issuer: Pubkey,
+    /// Timestamp of the last issue or renewal
+    pub issued_at: i64,
+    /// Timestamp after which the attestation no longer permits investing
+    pub expires_at: i64,
+    /// Set by the compliance authority; cleared on renewal
+    pub revoked: bool,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl InvestorAttestation {
+    pub const LEN: usize = 8 + 32 + 1 + 2 + 32 + 32 + 8 + 8 + 1 + 1 + VERSIONING_LEN;
+
+    /// Write a fresh attestation issued by `issuer` at `now`
+    pub fn record(
+        &mut self,
+        issuer: Pubkey,
+        tier: u8,
+        jurisdiction: [u8; 2],
+        kyc_hash: [u8; 32],
+        expires_at: i64,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            tier > 0 && jurisdiction.iter().all(u8::is_ascii_uppercase) && expires_at > now,
+            ErrorCode::InvalidAttestation
+        );
+        self.tier = tier;
+        self.jurisdiction = jurisdiction;
+        self.kyc_hash = kyc_hash;
+        self.issuer = issuer;
+        self.issued_at = now;
+        self.expires_at = expires_at;
+        self.revoked = false;
+        Ok(())
+    }
+
+    /// Check the attestation permits investing at `now`
+    pub fn verify(&self, now: i64) -> Result<()> {
+        require!(!self.revoked, ErrorCode::AttestationRevoked);
+        require!(now < self.expires_at, ErrorCode::AttestationExpired);
+        Ok(())
+    }
+}
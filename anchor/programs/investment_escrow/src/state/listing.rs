@@ -0,0 +1,48 @@
+//! Secondary-market listings
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::{RESERVED_LEN, VERSIONING_LEN};
+
+/// Listing - A seller's ask for some or all of a position's shares
+/// Filled atomically by buyers, in part or in full, until it expires
+#[account]
+pub struct Listing {
+    /// Position holder who created the listing
+    pub seller: Pubkey,
+    /// Position being sold (part of the PDA seeds)
+    pub investment_record: Pubkey,
+    /// Startup the position is in
+    pub startup_id: Pubkey,
+    /// Shares still for sale (in share mint base units)
+    pub shares_remaining: u64,
+    /// Ask in stablecoin base units per whole share
+    pub price_per_share: u64,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Timestamp after which the listing can no longer be filled
+    pub expires_at: i64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl Listing {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + VERSIONING_LEN;
+
+    /// Price of `shares` at the ask
+    pub fn cost(&self, shares: u64, decimals: u8) -> Result<u64> {
+        let scale = 10u128
+            .checked_pow(decimals as u32)
+            .ok_or(error!(ErrorCode::Overflow))?;
+        let cost = (shares as u128)
+            .checked_mul(self.price_per_share as u128)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / scale;
+        u64::try_from(cost).map_err(|_| error!(ErrorCode::Overflow))
+    }
+}
@@ -0,0 +1,56 @@
+//! Milestones that gate the release of a raise
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::{RESERVED_LEN, VERSIONING_LEN};
+
+/// Milestone - A tranche of a startup's raise unlocked by reaching a goal
+/// (e.g. "equipment purchased", "first 1,000 users")
+#[account]
+pub struct Milestone {
+    /// Startup the milestone belongs to
+    pub startup_id: Pubkey,
+    /// Sequential index within the startup (part of the PDA seeds)
+    pub index: u8,
+    /// Hash of the off-chain milestone description
+    pub description_hash: [u8; 32],
+    /// Share of the startup's net raise released for this milestone (basis points)
+    pub percent_bps: u16,
+    /// Target completion date
+    pub due_date: i64,
+    /// Milestone state
+    pub state: MilestoneState,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl Milestone {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 2 + 8 + 1 + 1 + VERSIONING_LEN;
+
+    /// Tranche released for this milestone out of `net_raised`
+    pub fn tranche(&self, net_raised: u64) -> Result<u64> {
+        let tranche = (net_raised as u128)
+            .checked_mul(self.percent_bps as u128)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / 10_000;
+        u64::try_from(tranche).map_err(|_| error!(ErrorCode::Overflow))
+    }
+}
+
+/// Milestone lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MilestoneState {
+    /// Awaiting review by the release authority
+    Pending,
+    /// Reached; its tranche may be proposed for release
+    Approved,
+    /// Tranche paid out to the startup
+    Released,
+    /// Rejected by the release authority
+    Rejected,
+}
@@ -0,0 +1,112 @@
+//! Program accounts, their layout versions and the v1 layouts they migrate from
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::errors::ErrorCode;
+
+pub mod v1;
+
+mod config;
+mod escrow;
+mod investment;
+mod investor;
+mod listing;
+mod milestone;
+mod release;
+mod startup;
+
+pub use config::*;
+pub use escrow::*;
+pub use investment::*;
+pub use investor::*;
+pub use listing::*;
+pub use milestone::*;
+pub use release::*;
+pub use startup::*;
+
+/// Layout version written into every account this program creates.
+/// v1: the original program's `ProgramConfig`, `EscrowState` and `InvestmentAccount`,
+/// with no version byte or reserved space (see `v1`); migrating converts them field
+/// by field. v2: the first versioned layouts. v3: `Startup` and `InvestmentAccount`
+/// fields carved out of the reserved space, which read as zero in a v2 account
+/// and are backfilled when it is migrated.
+pub const ACCOUNT_VERSION: u8 = 3;
+
+/// Spare bytes at the end of every account for future fields
+pub const RESERVED_LEN: usize = 64;
+
+/// Space versioning adds to every account (version byte + reserved bytes)
+pub const VERSIONING_LEN: usize = 1 + RESERVED_LEN;
+
+/// Accounts with a layout `version` that `migrate_account` can upgrade
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator {
+    /// Current allocated size, discriminator included
+    const SPACE: usize;
+
+    fn version(&self) -> u8;
+
+    fn set_version(&mut self, version: u8);
+}
+
+macro_rules! impl_versioned {
+    ($($account:ty),* $(,)?) => {
+        $(
+            impl Versioned for $account {
+                const SPACE: usize = <$account>::LEN;
+
+                fn version(&self) -> u8 {
+                    self.version
+                }
+
+                fn set_version(&mut self, version: u8) {
+                    self.version = version;
+                }
+            }
+        )*
+    };
+}
+
+impl_versioned!(
+    InvestmentAccount,
+    ProgramConfig,
+    EscrowState,
+    AcceptedMint,
+    InvestorProfile,
+    InvestorAttestation,
+    Startup,
+    Milestone,
+    ReleaseProposal,
+    Listing,
+);
+
+/// Decode a v2 account as `T` at `ACCOUNT_VERSION`
+/// The layouts match; fields v3 carved out of the reserved space read as zero.
+pub(crate) fn upgrade_account_data<T: Versioned>(data: &[u8]) -> Result<T> {
+    let mut account = T::try_deserialize(&mut &data[..])?;
+    require!(account.version() < ACCOUNT_VERSION, ErrorCode::AccountAlreadyMigrated);
+    account.set_version(ACCOUNT_VERSION);
+    Ok(account)
+}
+
+/// Decode a v1 account (identified by its allocated size) through its field-by-field
+/// conversion, or a v2 account as `upgrade_account_data` does
+pub(crate) fn upgrade_v1_account_data<V: v1::Layout>(data: &[u8]) -> Result<V::Current> {
+    if data.len() != V::LEN {
+        return upgrade_account_data::<V::Current>(data);
+    }
+    require!(
+        data[..8] == V::Current::DISCRIMINATOR,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    V::deserialize(&mut &data[8..])?.upgrade()
+}
+
+/// `amount x part / whole`, rounded down
+pub(crate) fn proportion(amount: u64, part: u64, whole: u64) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(part as u128)
+        .ok_or(error!(ErrorCode::Overflow))?
+        / whole as u128;
+    u64::try_from(share).map_err(|_| error!(ErrorCode::Overflow))
+}
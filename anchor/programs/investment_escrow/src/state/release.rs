@@ -0,0 +1,58 @@
+//! Multi-approver release proposals
+
+use anchor_lang::prelude::*;
+
+use super::*;
+
+/// Release Proposal - Pending transfer of a startup's escrow to its payout account
+/// Executable by anyone once `approval_threshold` approvers have approved it
+#[account]
+pub struct ReleaseProposal {
+    /// Startup whose escrowed funds would be released
+    pub startup_id: Pubkey,
+    /// Sequential id within the startup (part of the PDA seeds)
+    pub proposal_id: u64,
+    /// Milestone whose tranche is being released
+    pub milestone_index: u8,
+    /// USDC amount to release (in lamports)
+    pub amount: u64,
+    /// Startup payout token account receiving the funds
+    pub destination: Pubkey,
+    /// Approver who created the proposal
+    pub proposer: Pubkey,
+    /// Approvers who have approved so far
+    pub approvals: Vec<Pubkey>,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Timestamp after which the proposal can no longer be approved or executed
+    pub expires_at: i64,
+    /// Proposal status
+    pub status: ReleaseProposalStatus,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN],
+}
+
+impl ReleaseProposal {
+    pub const LEN: usize = 8 + 32 + 8 + 1 + 8 + 32 + 32
+        + (4 + 32 * ProgramConfig::MAX_RELEASE_APPROVERS) + 8 + 8 + 1 + 1 + VERSIONING_LEN;
+
+    /// Approvals that still belong to the current approver set
+    pub fn approval_count(&self, config: &ProgramConfig) -> usize {
+        self.approvals
+            .iter()
+            .filter(|approver| config.is_release_approver(approver))
+            .count()
+    }
+}
+
+/// Release proposal lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReleaseProposalStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
@@ -0,0 +1,177 @@
+//! Startups and their raise, release and settlement totals
+
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use super::{proportion, ACCOUNT_VERSION, RESERVED_LEN, VERSIONING_LEN};
+
+/// Startup - On-chain registry entry for an investable startup
+/// Created by the admin; `invest_usd` only accepts startups registered here
+#[account]
+pub struct Startup {
+    /// Startup's unique identifier (the `startup_id` used by clients)
+    pub startup_id: Pubkey,
+    /// Display name (e.g. "Monime", "DirectEd")
+    pub name: String,
+    /// Wallet that receives released funds
+    pub payout_wallet: Pubkey,
+    /// Accepted stablecoin the startup raises, releases and repays in
+    pub mint: Pubkey,
+    /// Soft cap: minimum net raise for the round to succeed (in USDC lamports)
+    pub funding_target: u64,
+    /// Maximum amount the startup may raise (in USDC lamports)
+    pub hard_cap: u64,
+    /// Total USDC invested so far
+    pub total_raised: u64,
+    /// Total USDC released to the startup
+    pub total_released: u64,
+    /// Total USDC refunded to investors
+    pub total_refunded: u64,
+    /// Timestamp from which investments are accepted
+    pub opens_at: i64,
+    /// Raise deadline; investments are rejected after it and the raise can be finalized
+    pub closes_at: i64,
+    /// Fundraising status
+    pub status: StartupStatus,
+    /// Number of release proposals created (next proposal id)
+    pub release_proposal_count: u64,
+    /// Number of milestones created (next milestone index)
+    pub milestone_count: u8,
+    /// Sum of all milestones' share of the raise, rejected ones excepted (in basis points)
+    pub milestone_bps_total: u16,
+    /// Share mint for the raise (seeds: ["share_mint", startup_id]); this PDA is its
+    /// mint and freeze authority
+    pub share_mint: Pubkey,
+    /// Stablecoin base units per whole share
+    pub share_price: u64,
+    /// Shares held by active positions, the base for distributions
+    pub active_shares: u64,
+    /// Royalty paid to the startup on secondary-market fills (basis points)
+    pub royalty_bps: u16,
+    /// Cumulative returns per share base unit, scaled by `REWARD_PRECISION`
+    pub reward_per_share: u128,
+    /// Total returns deposited into the distribution vault
+    pub total_distributed: u64,
+    /// Investment term in seconds; positions mature this long after `investment_date`
+    pub term_length: i64,
+    /// Total USDC deposited into the repayment vault
+    pub total_repaid: u64,
+    /// Amount owed to settled positions that repayments have not covered yet
+    pub total_shortfall: u64,
+    /// PDA bump
+    pub bump: u8,
+    /// Layout version (`ACCOUNT_VERSION`)
+    pub version: u8,
+    /// Return owed on principal at maturity in percent (0-100), set by the admin
+    pub expected_return: u8,
+    /// Principal plus expected return owed to every position that will be settled
+    pub total_owed: u64,
+    /// Escrow balance when the startup was cancelled (the pool refunds share)
+    pub cancelled_escrow_balance: u64,
+    /// Net raise when the startup was cancelled (the principal refunds are owed on)
+    pub cancelled_net_raised: u64,
+    /// Reserved for future fields
+    pub reserved: [u8; RESERVED_LEN - 1 - 8 - 8 - 8],
+}
+
+impl Startup {
+    pub const MAX_NAME_LEN: usize = 64;
+    // Fields after `version` were carved out of the reserved space
+    pub const LEN: usize = 8 + 32 + (4 + Self::MAX_NAME_LEN) + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 2
+        + 32 + 8 + 8 + 2 + 16 + 8 + 8 + 8 + 8 + 1 + VERSIONING_LEN;
+
+    /// Fixed-point scale of `reward_per_share`
+    pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+    /// Returns earned by `shares` since the first distribution
+    pub fn accumulated_returns(&self, shares: u64) -> Result<u64> {
+        let accumulated = (shares as u128)
+            .checked_mul(self.reward_per_share)
+            .ok_or(error!(ErrorCode::Overflow))?
+            / Self::REWARD_PRECISION;
+        u64::try_from(accumulated).map_err(|_| error!(ErrorCode::Overflow))
+    }
+
+    /// Shares bought by `principal` at the raise's share price
+    pub fn shares_for(&self, principal: u64, decimals: u8) -> Result<u64> {
+        let shares = 10u128
+            .checked_pow(decimals as u32)
+            .and_then(|scale| (principal as u128).checked_mul(scale))
+            .ok_or(error!(ErrorCode::Overflow))?
+            / self.share_price as u128;
+        u64::try_from(shares).map_err(|_| error!(ErrorCode::Overflow))
+    }
+
+    /// Part of `amount_due` the repayments so far cover: every position is paid
+    /// the same fraction (total repaid / total owed), capped at what it is owed
+    pub fn repaid_share(&self, amount_due: u64) -> Result<u64> {
+        if self.total_repaid >= self.total_owed {
+            return Ok(amount_due);
+        }
+        proportion(amount_due, self.total_repaid, self.total_owed)
+    }
+
+    /// Principal refunded to a position: in full, unless the startup was
+    /// cancelled after releasing funds, when every position gets the same
+    /// fraction (escrow balance / net raise at cancellation) whatever the order
+    pub fn refund_for(&self, principal: u64) -> Result<u64> {
+        if self.status != StartupStatus::Cancelled
+            || self.cancelled_escrow_balance >= self.cancelled_net_raised
+        {
+            return Ok(principal);
+        }
+        proportion(principal, self.cancelled_escrow_balance, self.cancelled_net_raised)
+    }
+
+    /// Fill the fields v3 carved out of the reserved space, which a v2 startup
+    /// reads as zero. The rate and the total owed (the sum of `amount_due` over
+    /// the startup's active, completed and defaulted records) are supplied by the
+    /// admin; a startup already cancelled takes its refund snapshot now.
+    pub fn backfill_v2(&mut self, expected_return: u8, total_owed: u64) -> Result<()> {
+        require!(expected_return <= 100, ErrorCode::InvalidReturnPercentage);
+        self.expected_return = expected_return;
+        self.total_owed = total_owed;
+        if self.status == StartupStatus::Cancelled {
+            self.cancelled_escrow_balance = self.escrow_balance()?;
+            self.cancelled_net_raised = self.net_raised()?;
+        }
+        Ok(())
+    }
+
+    /// Fail if the startup has not been migrated to the current layout
+    /// (its backfilled fields would read as zero)
+    pub fn require_current(&self) -> Result<()> {
+        require!(self.version == ACCOUNT_VERSION, ErrorCode::AccountNotMigrated);
+        Ok(())
+    }
+
+    /// USDC raised net of refunds (the base for milestone tranches)
+    pub fn net_raised(&self) -> Result<u64> {
+        self.total_raised
+            .checked_sub(self.total_refunded)
+            .ok_or(error!(ErrorCode::Underflow))
+    }
+
+    /// USDC still held in escrow on behalf of this startup
+    pub fn escrow_balance(&self) -> Result<u64> {
+        self.total_raised
+            .checked_sub(self.total_released)
+            .and_then(|balance| balance.checked_sub(self.total_refunded))
+            .ok_or(error!(ErrorCode::Underflow))
+    }
+}
+
+/// Startup fundraising status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StartupStatus {
+    /// Accepting investments inside its open/close window
+    Open,
+    /// Closed early by the admin, no further investments accepted
+    Closed,
+    /// Cancelled by the admin, investors may reclaim their principal
+    Cancelled,
+    /// Raise reached its soft cap; funds may be released against milestones
+    Funded,
+    /// Raise missed its soft cap; anyone may refund investors their principal
+    Failed,
+}
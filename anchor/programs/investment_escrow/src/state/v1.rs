@@ -0,0 +1,139 @@
+//! Account layouts of the original, unversioned program, exactly as it wrote them.
+//! Only these three account types existed then; their discriminators are unchanged.
+
+use super::*;
+
+/// The only stablecoin the original program accepted (devnet USDC)
+pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri1KseAPa9KUKFdgS2uK4JCT2TSXDKXrm");
+
+/// A v1 layout and its field-by-field conversion to the current one
+pub trait Layout: AnchorDeserialize {
+    /// Allocated size, discriminator included
+    const LEN: usize;
+
+    type Current: Versioned;
+
+    /// Map every v1 field onto the current layout; fields v1 lacked get explicit defaults
+    fn upgrade(self) -> Result<Self::Current>;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InvestmentAccount {
+    pub investor: Pubkey,
+    pub startup_id: Pubkey,
+    pub principal_usd: u64,
+    pub investment_date: i64,
+    pub expected_return: u8,
+    /// 0 = Active, 1 = Completed, 2 = Cancelled
+    pub status: u8,
+    pub bump: u8,
+}
+
+impl Layout for InvestmentAccount {
+    const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 1 + 1;
+
+    type Current = super::InvestmentAccount;
+
+    fn upgrade(self) -> Result<Self::Current> {
+        let status = match self.status {
+            0 => InvestmentStatus::Active,
+            1 => InvestmentStatus::Completed,
+            2 => InvestmentStatus::Cancelled,
+            _ => return err!(ErrorCode::InvalidMigrationTarget),
+        };
+        Ok(super::InvestmentAccount {
+            investor: self.investor,
+            startup_id: self.startup_id,
+            mint: USDC_MINT,
+            // v1 records keep their [b"investment", investor, startup_id] address
+            position_index: 0,
+            // v1 charged no platform fee
+            gross_usd: self.principal_usd,
+            principal_usd: self.principal_usd,
+            // v1 positions were never tokenised
+            shares: 0,
+            investment_date: self.investment_date,
+            expected_return: self.expected_return,
+            status,
+            reward_debt: 0,
+            unclaimed_returns: 0,
+            returns_claimed: 0,
+            settled_amount: 0,
+            shortfall: 0,
+            bump: self.bump,
+            version: ACCOUNT_VERSION,
+            // v1 records were always paid for by the investor
+            rent_payer: self.investor,
+            reserved: [0; RESERVED_LEN - 32],
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub min_investment: u64,
+    pub max_investment: u64,
+    pub platform_fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Layout for ProgramConfig {
+    const LEN: usize = 8 + 32 + 8 + 8 + 2 + 1;
+
+    type Current = super::ProgramConfig;
+
+    /// Settings v1 lacked take the values `initialize_program` gives a new config
+    fn upgrade(self) -> Result<Self::Current> {
+        Ok(super::ProgramConfig {
+            admin: self.admin,
+            min_investment: self.min_investment,
+            max_investment: self.max_investment,
+            platform_fee_bps: self.platform_fee_bps,
+            fee_mode: FeeMode::OnInvest,
+            cooling_off_period: 0,
+            handover_ttl: super::ProgramConfig::DEFAULT_HANDOVER_TTL,
+            pending_admin: None,
+            pending_admin_expires_at: 0,
+            release_approvers: vec![self.admin],
+            approval_threshold: 1,
+            release_proposal_ttl: super::ProgramConfig::DEFAULT_RELEASE_PROPOSAL_TTL,
+            compliance_authority: self.admin,
+            tier_limits: Vec::new(),
+            pauser: self.admin,
+            paused: 0,
+            min_holding_period: 0,
+            bump: self.bump,
+            version: ACCOUNT_VERSION,
+            reserved: [0; RESERVED_LEN],
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct EscrowState {
+    pub total_escrow: u64,
+    pub active_investments: u64,
+    pub release_authority: Pubkey,
+    pub bump: u8,
+}
+
+impl Layout for EscrowState {
+    const LEN: usize = 8 + 8 + 8 + 32 + 1;
+
+    type Current = super::EscrowState;
+
+    /// `total_escrow` is dropped: escrow totals are kept per mint in
+    /// `AcceptedMint`, which only counts deposits made to registered startups
+    fn upgrade(self) -> Result<Self::Current> {
+        Ok(super::EscrowState {
+            active_investments: self.active_investments,
+            release_authority: self.release_authority,
+            pending_release_authority: None,
+            pending_release_authority_expires_at: 0,
+            bump: self.bump,
+            version: ACCOUNT_VERSION,
+            reserved: [0; RESERVED_LEN],
+        })
+    }
+}
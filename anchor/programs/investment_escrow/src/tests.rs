@@ -0,0 +1,469 @@
+use super::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::spl_token_2022;
+use anchor_spl::token_interface::spl_token_2022::extension::{
+    permanent_delegate::PermanentDelegate, transfer_hook::TransferHook,
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+};
+
+/// Account data as the v1 program wrote it: discriminator, then the fields
+/// of the v1 layout, which fill its allocation exactly
+fn v1_bytes<V: v1::Layout + AnchorSerialize>(fields: &V) -> Vec<u8> {
+    let mut data = V::Current::DISCRIMINATOR.to_vec();
+    fields.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), V::LEN);
+    data
+}
+
+#[test]
+fn investment_account_migrates_from_v1() {
+    let v1 = v1::InvestmentAccount {
+        investor: Pubkey::new_unique(),
+        startup_id: Pubkey::new_unique(),
+        principal_usd: 50_000_000,
+        investment_date: 1_700_000_000,
+        expected_return: 25,
+        status: 2,
+        bump: 254,
+    };
+
+    let record = upgrade_v1_account_data::<v1::InvestmentAccount>(&v1_bytes(&v1)).unwrap();
+    assert_eq!(record.investor, v1.investor);
+    assert_eq!(record.startup_id, v1.startup_id);
+    assert_eq!(record.mint, v1::USDC_MINT);
+    assert_eq!(record.position_index, 0);
+    assert_eq!(record.gross_usd, 50_000_000);
+    assert_eq!(record.principal_usd, 50_000_000);
+    assert_eq!(record.shares, 0);
+    assert_eq!(record.investment_date, 1_700_000_000);
+    assert_eq!(record.expected_return, 25);
+    assert_eq!(record.status, InvestmentStatus::Cancelled);
+    assert_eq!(record.unclaimed_returns, 0);
+    assert_eq!(record.bump, 254);
+    assert_eq!(record.version, ACCOUNT_VERSION);
+    assert_eq!(record.rent_payer, v1.investor);
+    assert_eq!(record.reserved, [0; RESERVED_LEN - 32]);
+
+    let mut data = Vec::new();
+    record.try_serialize(&mut data).unwrap();
+    assert_eq!(data.len(), InvestmentAccount::LEN);
+}
+
+#[test]
+fn v1_investment_status_codes_map_onto_the_enum() {
+    for (code, status) in [
+        (0, Some(InvestmentStatus::Active)),
+        (1, Some(InvestmentStatus::Completed)),
+        (2, Some(InvestmentStatus::Cancelled)),
+        (3, None),
+    ] {
+        let v1 = v1::InvestmentAccount {
+            investor: Pubkey::new_unique(),
+            startup_id: Pubkey::new_unique(),
+            principal_usd: 1,
+            investment_date: 0,
+            expected_return: 0,
+            status: code,
+            bump: 255,
+        };
+        let record = upgrade_v1_account_data::<v1::InvestmentAccount>(&v1_bytes(&v1));
+        match status {
+            Some(status) => assert_eq!(record.unwrap().status, status),
+            None => assert_eq!(record.err(), Some(ErrorCode::InvalidMigrationTarget.into())),
+        }
+    }
+}
+
+#[test]
+fn program_config_migrates_from_v1() {
+    let admin = Pubkey::new_unique();
+    let v1 = v1::ProgramConfig {
+        admin,
+        min_investment: 1_000_000,
+        max_investment: 10_000_000_000,
+        platform_fee_bps: 250,
+        bump: 255,
+    };
+
+    let config = upgrade_v1_account_data::<v1::ProgramConfig>(&v1_bytes(&v1)).unwrap();
+    assert_eq!(config.admin, admin);
+    assert_eq!(config.min_investment, 1_000_000);
+    assert_eq!(config.max_investment, 10_000_000_000);
+    assert_eq!(config.platform_fee_bps, 250);
+    assert_eq!(config.fee_mode, FeeMode::OnInvest);
+    assert_eq!(config.cooling_off_period, 0);
+    assert_eq!(config.release_approvers, vec![admin]);
+    assert_eq!(config.approval_threshold, 1);
+    assert_eq!(config.compliance_authority, admin);
+    assert_eq!(config.pauser, admin);
+    assert_eq!(config.paused, 0);
+    assert!(config.tier_limits.is_empty());
+    assert_eq!(config.bump, 255);
+    assert_eq!(config.version, ACCOUNT_VERSION);
+    config.validate().unwrap();
+}
+
+#[test]
+fn escrow_state_migrates_from_v1() {
+    let v1 = v1::EscrowState {
+        total_escrow: 75_000_000,
+        active_investments: 42,
+        release_authority: Pubkey::new_unique(),
+        bump: 253,
+    };
+
+    let escrow_state = upgrade_v1_account_data::<v1::EscrowState>(&v1_bytes(&v1)).unwrap();
+    assert_eq!(escrow_state.active_investments, 42);
+    assert_eq!(escrow_state.release_authority, v1.release_authority);
+    assert_eq!(escrow_state.pending_release_authority, None);
+    assert_eq!(escrow_state.pending_release_authority_expires_at, 0);
+    assert_eq!(escrow_state.bump, 253);
+    assert_eq!(escrow_state.version, ACCOUNT_VERSION);
+}
+
+#[test]
+fn migrated_account_cannot_be_migrated_again() {
+    let v1 = v1::EscrowState {
+        total_escrow: 0,
+        active_investments: 1,
+        release_authority: Pubkey::new_unique(),
+        bump: 255,
+    };
+    let escrow_state = upgrade_v1_account_data::<v1::EscrowState>(&v1_bytes(&v1)).unwrap();
+    let mut data = Vec::new();
+    escrow_state.try_serialize(&mut data).unwrap();
+
+    assert_eq!(
+        upgrade_v1_account_data::<v1::EscrowState>(&data).err(),
+        Some(ErrorCode::AccountAlreadyMigrated.into())
+    );
+}
+
+#[test]
+fn migration_rejects_another_account_type() {
+    let v1 = v1::ProgramConfig {
+        admin: Pubkey::new_unique(),
+        min_investment: 0,
+        max_investment: 0,
+        platform_fee_bps: 0,
+        bump: 255,
+    };
+    // Same size as a v1 escrow state, but a config's discriminator
+    let mut data = v1_bytes(&v1);
+    data.resize(<v1::EscrowState as v1::Layout>::LEN, 0);
+
+    assert!(upgrade_v1_account_data::<v1::EscrowState>(&data).is_err());
+}
+
+#[test]
+fn v2_investment_record_keeps_its_fields() {
+    let mut v2 = investment_with_status(InvestmentStatus::Active, 5);
+    v2.version = 2;
+    let mut data = Vec::new();
+    v2.try_serialize(&mut data).unwrap();
+
+    let record = upgrade_v1_account_data::<v1::InvestmentAccount>(&data).unwrap();
+    assert_eq!(record.investor, v2.investor);
+    assert_eq!(record.shares, v2.shares);
+    assert_eq!(record.unclaimed_returns, 5);
+    assert_eq!(record.version, ACCOUNT_VERSION);
+}
+
+#[test]
+fn v2_startup_backfill_restores_settlement_and_refund_totals() {
+    let mut startup = startup_with_repayments(0, 0);
+    startup.version = 2;
+    startup.expected_return = 0;
+    startup.total_raised = 1_000;
+    startup.total_released = 400;
+    startup.status = StartupStatus::Cancelled;
+    let mut data = Vec::new();
+    startup.try_serialize(&mut data).unwrap();
+
+    let mut startup = upgrade_account_data::<Startup>(&data).unwrap();
+    assert_eq!(
+        startup.backfill_v2(101, 0),
+        Err(ErrorCode::InvalidReturnPercentage.into())
+    );
+    startup.backfill_v2(25, 1_250).unwrap();
+    startup.require_current().unwrap();
+    assert_eq!(startup.expected_return, 25);
+    assert_eq!(startup.total_owed, 1_250);
+    // Refunds share what was left in escrow at cancellation
+    assert_eq!(startup.cancelled_escrow_balance, 600);
+    assert_eq!(startup.cancelled_net_raised, 1_000);
+    assert_eq!(startup.refund_for(100).unwrap(), 60);
+}
+
+#[test]
+fn unmigrated_startup_is_refused() {
+    let mut startup = startup_with_repayments(0, 0);
+    startup.version = 2;
+    assert_eq!(
+        startup.require_current(),
+        Err(ErrorCode::AccountNotMigrated.into())
+    );
+}
+
+#[test]
+fn only_active_and_defaulted_investments_change_status_or_stay_open() {
+    use InvestmentStatus::*;
+    let all = [Active, Completed, Cancelled, Refunded, Transferred, Defaulted];
+
+    for status in all {
+        assert_eq!(status.is_terminal(), !matches!(status, Active | Defaulted));
+        for next in all {
+            let allowed = (status == Active && next != Active)
+                || (status == Defaulted && next == Completed);
+            assert_eq!(status.can_transition_to(next), allowed);
+        }
+    }
+}
+
+fn investment_with_status(status: InvestmentStatus, unclaimed_returns: u64) -> InvestmentAccount {
+    InvestmentAccount {
+        investor: Pubkey::new_unique(),
+        startup_id: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        position_index: 0,
+        gross_usd: 10_000_000,
+        principal_usd: 9_750_000,
+        shares: 9_750_000,
+        investment_date: 1_700_000_000,
+        expected_return: 25,
+        status,
+        reward_debt: 0,
+        unclaimed_returns,
+        returns_claimed: 0,
+        settled_amount: 0,
+        shortfall: 0,
+        bump: 255,
+        version: ACCOUNT_VERSION,
+        rent_payer: Pubkey::default(),
+        reserved: [0; RESERVED_LEN - 32],
+    }
+}
+
+#[test]
+fn only_terminal_investments_with_nothing_left_to_claim_can_be_closed() {
+    use InvestmentStatus::*;
+
+    for status in [Completed, Cancelled, Refunded, Transferred] {
+        investment_with_status(status, 0).require_closable().unwrap();
+        assert_eq!(
+            investment_with_status(status, 1).require_closable(),
+            Err(ErrorCode::UnclaimedReturnsPending.into())
+        );
+    }
+    for status in [Active, Defaulted] {
+        assert_eq!(
+            investment_with_status(status, 0).require_closable(),
+            Err(ErrorCode::InvestmentNotTerminal.into())
+        );
+    }
+}
+
+#[test]
+fn closing_refunds_whoever_paid_the_rent() {
+    let mut record = investment_with_status(InvestmentStatus::Transferred, 0);
+    // Records created before the payer was tracked refund the investor
+    assert_eq!(record.rent_recipient(), record.investor);
+
+    let previous_holder = Pubkey::new_unique();
+    record.rent_payer = previous_holder;
+    assert_eq!(record.rent_recipient(), previous_holder);
+}
+
+#[test]
+fn annual_cap_window_spans_a_year() {
+    const DAY: i64 = 24 * 60 * 60;
+    let limit = TierLimit {
+        lifetime_cap: u64::MAX,
+        annual_cap: 100,
+    };
+    let mut profile = InvestorProfile {
+        investor: Pubkey::new_unique(),
+        investment_count: 0,
+        lifetime_invested: 0,
+        monthly_invested: [0; InvestorProfile::PERIODS],
+        current_period: 0,
+        bump: 255,
+        version: ACCOUNT_VERSION,
+        reserved: [0; RESERVED_LEN],
+    };
+    // Start on a bucket boundary, in late 2023
+    let start = 647 * InvestorProfile::PERIOD_SECONDS;
+    profile.record_investment(100, start, &limit).unwrap();
+
+    // 360 days on (twelve 30-day months) the investment still counts
+    assert_eq!(
+        profile.record_investment(1, start + 360 * DAY, &limit),
+        Err(ErrorCode::AnnualCapExceeded.into())
+    );
+    assert_eq!(profile.annual_invested().unwrap(), 100);
+
+    // Once a full year has passed it has left the window
+    profile.record_investment(100, start + 365 * DAY, &limit).unwrap();
+    assert_eq!(profile.annual_invested().unwrap(), 100);
+    assert_eq!(profile.lifetime_invested, 200);
+}
+
+/// Token-2022 mint data carrying `extensions`, configured by `init`
+fn token_2022_mint(
+    extensions: &[ExtensionType],
+    init: impl FnOnce(&mut StateWithExtensionsMut<spl_token_2022::state::Mint>),
+) -> Vec<u8> {
+    let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+        .unwrap();
+    let mut data = vec![0; len];
+    let mut mint =
+        StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+            .unwrap();
+    init(&mut mint);
+    mint.base.decimals = 6;
+    mint.base.is_initialized = true;
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+    data
+}
+
+fn check_mint_extensions(mut data: Vec<u8>) -> Result<()> {
+    let key = Pubkey::new_unique();
+    let owner = spl_token_2022::ID;
+    let mut lamports = 0;
+    let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+    AcceptedMint::check_extensions(&mint)
+}
+
+#[test]
+fn inert_transfer_hook_and_permanent_delegate_are_accepted() {
+    // A hook with an authority but no program, as on mints that reserve one
+    let data = token_2022_mint(
+        &[ExtensionType::TransferHook, ExtensionType::PermanentDelegate],
+        |mint| {
+            let hook = mint.init_extension::<TransferHook>(true).unwrap();
+            hook.authority = Some(Pubkey::new_unique()).try_into().unwrap();
+            mint.init_extension::<PermanentDelegate>(true).unwrap();
+        },
+    );
+    check_mint_extensions(data).unwrap();
+}
+
+#[test]
+fn live_transfer_hook_or_permanent_delegate_is_rejected() {
+    let hooked = token_2022_mint(&[ExtensionType::TransferHook], |mint| {
+        let hook = mint.init_extension::<TransferHook>(true).unwrap();
+        hook.program_id = Some(Pubkey::new_unique()).try_into().unwrap();
+    });
+    assert_eq!(
+        check_mint_extensions(hooked),
+        Err(ErrorCode::UnsupportedMintExtension.into())
+    );
+
+    let delegated = token_2022_mint(&[ExtensionType::PermanentDelegate], |mint| {
+        let delegate = mint.init_extension::<PermanentDelegate>(true).unwrap();
+        delegate.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+    });
+    assert_eq!(
+        check_mint_extensions(delegated),
+        Err(ErrorCode::UnsupportedMintExtension.into())
+    );
+}
+
+#[test]
+fn share_math_rejects_decimals_beyond_u128() {
+    let startup = startup_with_repayments(0, 0);
+    assert_eq!(startup.shares_for(1_000_000, 6).unwrap(), 1_000_000);
+    assert_eq!(
+        startup.shares_for(1_000_000, 39),
+        Err(ErrorCode::Overflow.into())
+    );
+
+    let listing = Listing {
+        seller: Pubkey::new_unique(),
+        investment_record: Pubkey::new_unique(),
+        startup_id: Pubkey::new_unique(),
+        shares_remaining: 1_000_000,
+        price_per_share: 2_000_000,
+        created_at: 0,
+        expires_at: 1,
+        bump: 255,
+        version: ACCOUNT_VERSION,
+        reserved: [0; RESERVED_LEN],
+    };
+    assert_eq!(listing.cost(500_000, 6).unwrap(), 1_000_000);
+    assert_eq!(listing.cost(500_000, u8::MAX), Err(ErrorCode::Overflow.into()));
+}
+
+fn startup_with_repayments(total_owed: u64, total_repaid: u64) -> Startup {
+    Startup {
+        startup_id: Pubkey::new_unique(),
+        name: "Monime".to_string(),
+        payout_wallet: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        funding_target: 0,
+        hard_cap: u64::MAX,
+        total_raised: 0,
+        total_released: 0,
+        total_refunded: 0,
+        opens_at: 0,
+        closes_at: 1,
+        status: StartupStatus::Funded,
+        release_proposal_count: 0,
+        milestone_count: 0,
+        milestone_bps_total: 0,
+        share_mint: Pubkey::new_unique(),
+        share_price: 1_000_000,
+        active_shares: 0,
+        royalty_bps: 0,
+        reward_per_share: 0,
+        total_distributed: 0,
+        term_length: 1,
+        total_repaid,
+        total_shortfall: 0,
+        bump: 255,
+        version: ACCOUNT_VERSION,
+        expected_return: 25,
+        total_owed,
+        cancelled_escrow_balance: 0,
+        cancelled_net_raised: 0,
+        reserved: [0; RESERVED_LEN - 1 - 8 - 8 - 8],
+    }
+}
+
+#[test]
+fn settlements_are_pro_rata_whatever_the_order() {
+    // Two positions owed 125 and 250; the startup has repaid 150 of 375
+    let startup = startup_with_repayments(375, 150);
+    assert_eq!(startup.repaid_share(125).unwrap(), 50);
+    assert_eq!(startup.repaid_share(250).unwrap(), 100);
+
+    // A later repayment raises both positions' entitlement to the full amount
+    let startup = startup_with_repayments(375, 375);
+    assert_eq!(startup.repaid_share(125).unwrap(), 125);
+    assert_eq!(startup.repaid_share(250).unwrap(), 250);
+
+    // Repaying more than is owed never pays a position more than its due
+    let startup = startup_with_repayments(375, 1_000);
+    assert_eq!(startup.repaid_share(250).unwrap(), 250);
+}
+
+#[test]
+fn refunds_after_a_release_are_pro_rata() {
+    let mut startup = startup_with_repayments(0, 0);
+    startup.status = StartupStatus::Cancelled;
+    // 100 raised by two positions of 40 and 60, then 50 released before cancelling
+    startup.cancelled_escrow_balance = 50;
+    startup.cancelled_net_raised = 100;
+    assert_eq!(startup.refund_for(40).unwrap(), 20);
+    assert_eq!(startup.refund_for(60).unwrap(), 30);
+
+    // Nothing released: principal back in full
+    startup.cancelled_escrow_balance = 100;
+    assert_eq!(startup.refund_for(40).unwrap(), 40);
+
+    // Failed raises never released anything
+    startup.status = StartupStatus::Failed;
+    startup.cancelled_escrow_balance = 0;
+    assert_eq!(startup.refund_for(40).unwrap(), 40);
+}
@@ -2,6 +2,9 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import * as token from "@solana/spl-token";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { strict as assert } from "assert";
+import * as fs from "fs";
+import * as path from "path";
 import { SalonevestProgram } from "../target/types/investment_escrow";
 
 describe("SaloneVest Investment Program", () => {
@@ -15,17 +18,195 @@ describe("SaloneVest Investment Program", () => {
   let adminTokenAccount: PublicKey;
   let investorTokenAccount: PublicKey;
   let escrowTokenAccount: PublicKey;
+  let treasuryTokenAccount: PublicKey;
+  let feePda: PublicKey;
 
   const investor = Keypair.generate();
+  // Second KYC'd wallet that positions are transferred or sold to
+  const receiver = Keypair.generate();
   const admin = provider.wallet.publicKey;
 
-  // USDC Mint on Devnet
-  const USDC_MINT = new PublicKey("4zMMC9srt5Ri1KseAPa9KUKFdgS2uK4JCT2TSXDKXrm");
+  // Keypair committed under tests/keys
+  const loadKeypair = (name: string): Keypair =>
+    Keypair.fromSecretKey(
+      Uint8Array.from(JSON.parse(fs.readFileSync(path.join(__dirname, "keys", `${name}.json`), "utf8")))
+    );
+
+  // Localnet test USDC (`cluster_mints::localnet::USDC`), created in `before`
+  const usdcMintKeypair = loadKeypair("localnet-usdc");
+  const USDC_MINT = usdcMintKeypair.publicKey;
   const STARTUP_ID = new PublicKey("11111111111111111111111111111111");
 
+  // Allowlist entry for USDC (holds the per-mint escrow totals)
+  const [acceptedMintPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("accepted_mint"), USDC_MINT.toBuffer()],
+    program.programId
+  );
+
+  // Share mint for the startup's raise (mint authority is the startup PDA)
+  const [shareMintPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("share_mint"), STARTUP_ID.toBuffer()],
+    program.programId
+  );
+
+  // Token balance of an account, in base units
+  const balanceOf = async (
+    account: PublicKey,
+    tokenProgram: PublicKey = token.TOKEN_PROGRAM_ID
+  ): Promise<bigint> =>
+    (await token.getAccount(provider.connection, account, undefined, tokenProgram)).amount;
+
+  // `owner`'s share account for a share mint (share mints are Token-2022)
+  const shareAccountOf = (shareMint: PublicKey, owner: PublicKey): PublicKey =>
+    token.getAssociatedTokenAddressSync(shareMint, owner, false, token.TOKEN_2022_PROGRAM_ID);
+
+  const shareBalanceOf = (shareMint: PublicKey, owner: PublicKey): Promise<bigint> =>
+    balanceOf(shareAccountOf(shareMint, owner), token.TOKEN_2022_PROGRAM_ID);
+
+  // PDA of this program for `seeds`
+  const pda = (...seeds: Buffer[]): PublicKey =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // Decoded `name` event the program emitted in a confirmed transaction
+  const eventOf = async (signature: string, name: string): Promise<Record<string, any>> => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const event = Array.from(parser.parseLogs(tx!.meta!.logMessages!)).find((event) => event.name === name);
+    assert.ok(event, `no ${name} event in ${signature}`);
+    return event.data;
+  };
+
+  // Register an open startup at 1 USDC per share, paid out to the admin
+  // (a 1 USDC soft cap and 100,000 USDC hard cap in USDC unless overridden)
+  const registerStartup = async (
+    startupId: PublicKey,
+    name: string,
+    { fundingTarget = 1_000_000, hardCap = 100_000_000_000, mint = USDC_MINT } = {}
+  ) => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .registerStartup(
+        startupId,
+        name,
+        admin,
+        new anchor.BN(fundingTarget),
+        new anchor.BN(hardCap),
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 30 * 24 * 60 * 60),
+        new anchor.BN(365 * 24 * 60 * 60),
+        25,
+        new anchor.BN(1_000_000)
+      )
+      .accounts({
+        admin: admin,
+        config: pda(Buffer.from("config")),
+        startup: pda(Buffer.from("startup"), startupId.toBuffer()),
+        acceptedMint: pda(Buffer.from("accepted_mint"), mint.toBuffer()),
+        mint: mint,
+        shareMint: pda(Buffer.from("share_mint"), startupId.toBuffer()),
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  };
+
+  // Invest `amount` USDC from the investor into a startup; returns the new position's record
+  const invest = async (startupId: PublicKey, amount: number): Promise<PublicKey> => {
+    const investorProfilePda = pda(Buffer.from("investor"), investor.publicKey.toBuffer());
+    const profile = await program.account.investorProfile.fetchNullable(investorProfilePda);
+    const positionIndex = profile ? profile.investmentCount : new anchor.BN(0);
+    const investmentPda = pda(
+      Buffer.from("investment"),
+      investor.publicKey.toBuffer(),
+      positionIndex.toArrayLike(Buffer, "le", 8)
+    );
+    const shareMint = pda(Buffer.from("share_mint"), startupId.toBuffer());
+
+    await program.methods
+      .investUsd(new anchor.BN(amount), startupId)
+      .accounts({
+        investor: investor.publicKey,
+        investorTokenAccount: investorTokenAccount,
+        programEscrowAta: escrowTokenAccount,
+        escrowPda: pda(Buffer.from("escrow_authority")),
+        treasuryAta: treasuryTokenAccount,
+        feePda: feePda,
+        investorProfile: investorProfilePda,
+        attestation: pda(Buffer.from("attestation"), investor.publicKey.toBuffer()),
+        investmentRecord: investmentPda,
+        startup: pda(Buffer.from("startup"), startupId.toBuffer()),
+        acceptedMint: acceptedMintPda,
+        config: pda(Buffer.from("config")),
+        escrowState: pda(Buffer.from("escrow")),
+        mint: USDC_MINT,
+        shareMint: shareMint,
+        investorShareAccount: shareAccountOf(shareMint, investor.publicKey),
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+      })
+      .signers([investor])
+      .rpc();
+
+    return investmentPda;
+  };
+
+  // Issue a tier-1 attestation to `wallet`
+  const attest = async (wallet: PublicKey) => {
+    await program.methods
+      .issueAttestation(
+        wallet,
+        1,
+        Array.from(Buffer.from("GB")),
+        Array.from(Buffer.alloc(32, 7)),
+        new anchor.BN(Math.floor(Date.now() / 1000) + 365 * 24 * 60 * 60)
+      )
+      .accounts({
+        complianceAuthority: admin,
+        config: pda(Buffer.from("config")),
+        attestation: pda(Buffer.from("attestation"), wallet.toBuffer()),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  };
+
+  // Accounts for cancel_investment and refund_investment on the investor's position
+  const cancelAccounts = (authority: PublicKey, investmentPda: PublicKey, startupId: PublicKey) => {
+    const shareMint = pda(Buffer.from("share_mint"), startupId.toBuffer());
+    return {
+      authority: authority,
+      investmentRecord: investmentPda,
+      startup: pda(Buffer.from("startup"), startupId.toBuffer()),
+      acceptedMint: acceptedMintPda,
+      config: pda(Buffer.from("config")),
+      escrowState: pda(Buffer.from("escrow")),
+      investorTokenAccount: investorTokenAccount,
+      programEscrowAta: escrowTokenAccount,
+      escrowPda: pda(Buffer.from("escrow_authority")),
+      mint: USDC_MINT,
+      shareMint: shareMint,
+      investorShareAccount: shareAccountOf(shareMint, investor.publicKey),
+      tokenProgram: token.TOKEN_PROGRAM_ID,
+      shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+    };
+  };
+
   before(async () => {
     // Airdrop SOL to investor
     await provider.connection.requestAirdrop(investor.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.requestAirdrop(receiver.publicKey, 2 * anchor.web3.LAMPORTS_PER_SOL);
+
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    if (!(await provider.connection.getAccountInfo(USDC_MINT))) {
+      await token.createMint(provider.connection, payer, admin, null, 6, usdcMintKeypair);
+    }
 
     // Get or create token accounts
     adminTokenAccount = (await token.getOrCreateAssociatedTokenAccount(
@@ -42,6 +223,10 @@ describe("SaloneVest Investment Program", () => {
       investor.publicKey
     )).address;
 
+    // 1,000 test USDC each to invest and to deposit returns and repayments from
+    await token.mintTo(provider.connection, payer, USDC_MINT, investorTokenAccount, admin, 1_000_000_000);
+    await token.mintTo(provider.connection, payer, USDC_MINT, adminTokenAccount, admin, 1_000_000_000);
+
     const escrowPda = PublicKey.findProgramAddressSync(
       [Buffer.from("escrow_authority")],
       program.programId
@@ -54,6 +239,19 @@ describe("SaloneVest Investment Program", () => {
       escrowPda,
       true
     )).address;
+
+    feePda = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_authority")],
+      program.programId
+    )[0];
+
+    treasuryTokenAccount = (await token.getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      provider.wallet as any,
+      USDC_MINT,
+      feePda,
+      true
+    )).address;
   });
 
   it("Initializes the program", async () => {
@@ -68,7 +266,14 @@ describe("SaloneVest Investment Program", () => {
     )[0];
 
     const tx = await program.methods
-      .initializeProgram(admin, new anchor.BN(1_000_000), new anchor.BN(10_000_000_000), 500)
+      .initializeProgram(
+        admin,
+        new anchor.BN(1_000_000),
+        new anchor.BN(10_000_000_000),
+        500,
+        { onInvest: {} },
+        new anchor.BN(48 * 60 * 60) // 48-hour cooling-off period
+      )
       .accounts({
         payer: admin,
         config: configPda,
@@ -82,12 +287,249 @@ describe("SaloneVest Investment Program", () => {
 
     // Verify config was set
     const config = await program.account.programConfig.fetch(configPda);
-    console.log("Config:", {
-      admin: config.admin.toString(),
-      minInvestment: config.minInvestment.toString(),
-      maxInvestment: config.maxInvestment.toString(),
-      platformFeeBps: config.platformFeeBps,
-    });
+    assert.ok(config.admin.equals(admin));
+    assert.equal(config.minInvestment.toNumber(), 1_000_000);
+    assert.equal(config.maxInvestment.toNumber(), 10_000_000_000);
+    assert.equal(config.platformFeeBps, 500);
+    assert.deepEqual(config.feeMode, { onInvest: {} });
+    assert.equal(config.coolingOffPeriod.toNumber(), 48 * 60 * 60);
+    assert.equal(config.paused, 0);
+
+    const escrowState = await program.account.escrowState.fetch(escrowStatePda);
+    assert.ok(escrowState.releaseAuthority.equals(admin));
+    assert.equal(escrowState.activeInvestments.toNumber(), 0);
+  });
+
+  it("Updates the platform fee", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    const escrowStatePda = pda(Buffer.from("escrow"));
+    const newReleaseAuthority = Keypair.generate().publicKey;
+
+    await program.methods
+      .updateConfig(null, null, 250, null, null, null, newReleaseAuthority, null, null, null)
+      .accounts({
+        admin: admin,
+        config: configPda,
+        escrowState: escrowStatePda,
+      })
+      .rpc();
+
+    const config = await program.account.programConfig.fetch(configPda);
+    assert.equal(config.platformFeeBps, 250);
+    // Values passed as null are left unchanged
+    assert.equal(config.minInvestment.toNumber(), 1_000_000);
+    assert.deepEqual(config.feeMode, { onInvest: {} });
+
+    // The new release authority is only proposed until it accepts
+    const escrowState = await program.account.escrowState.fetch(escrowStatePda);
+    assert.ok(escrowState.releaseAuthority.equals(admin));
+    assert.ok(escrowState.pendingReleaseAuthority!.equals(newReleaseAuthority));
+
+    // Withdraw the proposal; the admin stays release authority for the tests below
+    await program.methods
+      .cancelReleaseAuthorityHandover()
+      .accounts({ authority: admin, config: configPda, escrowState: escrowStatePda })
+      .rpc();
+    const cancelled = await program.account.escrowState.fetch(escrowStatePda);
+    assert.equal(cancelled.pendingReleaseAuthority, null);
+  });
+
+  it("Adds USDC to the accepted-mint allowlist", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    const [escrowPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_authority")],
+      program.programId
+    );
+
+    await program.methods
+      .addAcceptedMint()
+      .accounts({
+        admin: admin,
+        config: configPda,
+        mint: USDC_MINT,
+        acceptedMint: acceptedMintPda,
+        escrowAta: escrowTokenAccount,
+        escrowPda: escrowPda,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const acceptedMint = await program.account.acceptedMint.fetch(acceptedMintPda);
+    assert.ok(acceptedMint.mint.equals(USDC_MINT));
+    assert.equal(acceptedMint.enabled, true);
+    assert.equal(acceptedMint.totalEscrow.toNumber(), 0);
+    assert.equal(acceptedMint.totalFeesCollected.toNumber(), 0);
+  });
+
+  it("Refuses a mint outside the cluster's stablecoin list", async () => {
+    const otherMint = await token.createMint(
+      provider.connection,
+      (provider.wallet as anchor.Wallet).payer,
+      admin,
+      null,
+      6
+    );
+    const escrowPda = pda(Buffer.from("escrow_authority"));
+
+    await assert.rejects(
+      program.methods
+        .addAcceptedMint()
+        .accounts({
+          admin: admin,
+          config: pda(Buffer.from("config")),
+          mint: otherMint,
+          acceptedMint: pda(Buffer.from("accepted_mint"), otherMint.toBuffer()),
+          escrowAta: token.getAssociatedTokenAddressSync(otherMint, escrowPda, true),
+          escrowPda: escrowPda,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc(),
+      /MintNotForCluster/
+    );
+
+    const acceptedMint = await program.account.acceptedMint.fetchNullable(
+      pda(Buffer.from("accepted_mint"), otherMint.toBuffer())
+    );
+    assert.equal(acceptedMint, null);
+  });
+
+  it("Registers a startup in the on-chain catalog", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    const [startupPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("startup"), STARTUP_ID.toBuffer()],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .registerStartup(
+        STARTUP_ID,
+        "Monime",
+        admin,
+        new anchor.BN(10_000_000), // 10 USDC soft cap
+        new anchor.BN(100_000_000_000), // 100,000 USDC hard cap
+        new anchor.BN(now - 60),
+        new anchor.BN(now + 30 * 24 * 60 * 60),
+        new anchor.BN(365 * 24 * 60 * 60), // 1 year term
+        25, // 25% return owed at maturity
+        new anchor.BN(1_000_000) // 1 USDC per share
+      )
+      .accounts({
+        admin: admin,
+        config: configPda,
+        startup: startupPda,
+        acceptedMint: acceptedMintPda,
+        mint: USDC_MINT,
+        shareMint: shareMintPda,
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const startup = await program.account.startup.fetch(startupPda);
+    assert.equal(startup.name, "Monime");
+    assert.ok(startup.payoutWallet.equals(admin));
+    assert.ok(startup.mint.equals(USDC_MINT));
+    assert.equal(startup.hardCap.toNumber(), 100_000_000_000);
+    assert.equal(startup.expectedReturn, 25);
+    assert.equal(startup.totalRaised.toNumber(), 0);
+    assert.deepEqual(startup.status, { open: {} });
+    assert.ok(startup.shareMint.equals(shareMintPda));
+    assert.equal(startup.sharePrice.toNumber(), 1_000_000);
+
+    // The share mint mirrors the stablecoin's decimals and is controlled by the startup PDA,
+    // which is also its permanent delegate
+    const shareMint = await token.getMint(
+      provider.connection,
+      shareMintPda,
+      undefined,
+      token.TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(shareMint.decimals, 6);
+    assert.ok(shareMint.mintAuthority.equals(startupPda));
+    assert.ok(token.getPermanentDelegate(shareMint).delegate.equals(startupPda));
+    assert.equal(shareMint.supply, BigInt(0));
+  });
+
+  it("Sets per-tier investment caps", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    await program.methods
+      .setTierLimits([
+        // Tier 1 (retail)
+        {
+          lifetimeCap: new anchor.BN(10_000_000_000), // 10,000 USDC
+          annualCap: new anchor.BN(5_000_000_000), // 5,000 USDC
+        },
+      ])
+      .accounts({
+        admin: admin,
+        config: configPda,
+      })
+      .rpc();
+
+    const config = await program.account.programConfig.fetch(configPda);
+    assert.equal(config.tierLimits.length, 1);
+    assert.equal(config.tierLimits[0].lifetimeCap.toNumber(), 10_000_000_000);
+    assert.equal(config.tierLimits[0].annualCap.toNumber(), 5_000_000_000);
+  });
+
+  it("Issues a KYC attestation to the investor", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    const [attestationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("attestation"), investor.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    // Only a hash of the off-chain KYC record goes on-chain
+    const kycHash = Array.from(Buffer.alloc(32, 7));
+
+    await program.methods
+      .issueAttestation(
+        investor.publicKey,
+        1,
+        Array.from(Buffer.from("GB")),
+        kycHash,
+        new anchor.BN(now + 365 * 24 * 60 * 60)
+      )
+      .accounts({
+        complianceAuthority: admin,
+        config: configPda,
+        attestation: attestationPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const attestation = await program.account.investorAttestation.fetch(attestationPda);
+    assert.ok(attestation.investor.equals(investor.publicKey));
+    assert.equal(attestation.tier, 1);
+    assert.deepEqual(attestation.jurisdiction, Array.from(Buffer.from("GB")));
+    assert.deepEqual(attestation.kycHash, kycHash);
+    assert.ok(attestation.issuer.equals(admin));
+    assert.equal(attestation.revoked, false);
   });
 
   it("Records an investment with USDC transfer", async () => {
@@ -104,12 +546,21 @@ describe("SaloneVest Investment Program", () => {
       )
     );
 
-    // Create investment record PDA
+    // Investor profile PDA holds the per-investor position counter
+    const [investorProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("investor"), investor.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const profile = await program.account.investorProfile.fetchNullable(investorProfilePda);
+    const positionIndex = profile ? profile.investmentCount : new anchor.BN(0);
+
+    // Create investment record PDA for the next position
     const [investmentPda] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("investment"),
         investor.publicKey.toBuffer(),
-        STARTUP_ID.toBuffer(),
+        positionIndex.toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
@@ -129,55 +580,127 @@ describe("SaloneVest Investment Program", () => {
       program.programId
     );
 
+    const [startupPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("startup"), STARTUP_ID.toBuffer()],
+      program.programId
+    );
+
     const investmentAmount = new anchor.BN(50_000_000); // 50 USDC
 
+    const investorShareAccount = shareAccountOf(shareMintPda, investor.publicKey);
+
+    const investorBalanceBefore = await balanceOf(investorTokenAccount);
+    const escrowBalanceBefore = await balanceOf(escrowTokenAccount);
+    const treasuryBalanceBefore = await balanceOf(treasuryTokenAccount);
+
     const investTx = await program.methods
-      .investUsd(investmentAmount, STARTUP_ID, 25) // 25% expected return
+      .investUsd(investmentAmount, STARTUP_ID)
       .accounts({
         investor: investor.publicKey,
         investorTokenAccount: investorTokenAccount,
         programEscrowAta: escrowTokenAccount,
         escrowPda: escrowPda,
+        treasuryAta: treasuryTokenAccount,
+        feePda: feePda,
+        investorProfile: investorProfilePda,
+        attestation: PublicKey.findProgramAddressSync(
+          [Buffer.from("attestation"), investor.publicKey.toBuffer()],
+          program.programId
+        )[0],
         investmentRecord: investmentPda,
+        startup: startupPda,
+        acceptedMint: acceptedMintPda,
         config: configPda,
         escrowState: escrowStatePda,
-        usdcMint: USDC_MINT,
+        mint: USDC_MINT,
+        shareMint: shareMintPda,
+        investorShareAccount: investorShareAccount,
         tokenProgram: token.TOKEN_PROGRAM_ID,
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
       })
       .signers([investor])
-      .rpc();
+      .rpc({ commitment: "confirmed" });
 
     console.log("✅ Investment recorded");
     console.log("Transaction signature:", investTx);
 
+    // 2.5% platform fee goes to the treasury, the rest is principal in escrow
+    const fee = 1_250_000;
+    const principal = 48_750_000;
+
     // Verify investment record
     const investmentRecord = await program.account.investmentAccount.fetch(investmentPda);
-    console.log("Investment Record:", {
-      investor: investmentRecord.investor.toString(),
-      startupId: investmentRecord.startupId.toString(),
-      principalUsd: investmentRecord.principalUsd.toString(),
-      expectedReturn: investmentRecord.expectedReturn,
-      status: investmentRecord.status,
-      investmentDate: investmentRecord.investmentDate.toString(),
-    });
+    assert.ok(investmentRecord.investor.equals(investor.publicKey));
+    assert.ok(investmentRecord.startupId.equals(STARTUP_ID));
+    assert.ok(investmentRecord.mint.equals(USDC_MINT));
+    assert.ok(investmentRecord.positionIndex.eq(positionIndex));
+    assert.equal(investmentRecord.grossUsd.toNumber(), 50_000_000);
+    assert.equal(investmentRecord.principalUsd.toNumber(), principal);
+    // The return rate comes from the startup, not from the investor
+    assert.equal(investmentRecord.expectedReturn, 25);
+    assert.deepEqual(investmentRecord.status, { active: {} });
+
+    // 1 USDC per share, so one share base unit per stablecoin base unit
+    assert.equal(investmentRecord.shares.toNumber(), principal);
+
+    // Shares sit frozen in the investor's wallet and match the position
+    const shareAccount = await token.getAccount(
+      provider.connection,
+      investorShareAccount,
+      undefined,
+      token.TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(shareAccount.amount, BigInt(principal));
+    assert.equal(shareAccount.isFrozen, true);
 
-    // Verify escrow state was updated
+    // Token balances moved by exactly the gross amount, split into principal and fee
+    assert.equal(investorBalanceBefore - (await balanceOf(investorTokenAccount)), BigInt(50_000_000));
+    assert.equal((await balanceOf(escrowTokenAccount)) - escrowBalanceBefore, BigInt(principal));
+    assert.equal((await balanceOf(treasuryTokenAccount)) - treasuryBalanceBefore, BigInt(fee));
+
+    // Verify escrow totals were updated
     const escrowState = await program.account.escrowState.fetch(escrowStatePda);
-    console.log("Escrow State:", {
-      totalEscrow: escrowState.totalEscrow.toString(),
-      activeInvestments: escrowState.activeInvestments.toString(),
-    });
+    const acceptedMint = await program.account.acceptedMint.fetch(acceptedMintPda);
+    assert.equal(acceptedMint.totalEscrow.toNumber(), principal);
+    assert.equal(acceptedMint.totalFeesCollected.toNumber(), fee);
+    assert.equal(escrowState.activeInvestments.toNumber(), 1);
+
+    const startup = await program.account.startup.fetch(startupPda);
+    assert.equal(startup.totalRaised.toNumber(), principal);
+    assert.equal(startup.activeShares.toNumber(), principal);
+    // Principal plus the 25% return is owed at maturity
+    assert.equal(startup.totalOwed.toNumber(), principal * 1.25);
+
+    const invested = await eventOf(investTx, "investmentMade");
+    assert.ok(invested.investor.equals(investor.publicKey));
+    assert.ok(invested.startupId.equals(STARTUP_ID));
+    assert.ok(invested.investmentRecord.equals(investmentPda));
+    assert.ok(invested.positionIndex.eq(positionIndex));
+    assert.equal(invested.grossAmount.toNumber(), 50_000_000);
+    assert.equal(invested.fee.toNumber(), fee);
+    assert.equal(invested.principal.toNumber(), principal);
+    assert.equal(invested.shares.toNumber(), principal);
+    assert.equal(invested.expectedReturn, 25);
+    assert.equal(invested.startupTotalRaised.toNumber(), principal);
+    assert.ok(invested.mint.equals(USDC_MINT));
+    assert.equal(invested.totalEscrow.toNumber(), principal);
+
+    const investorProfile = await program.account.investorProfile.fetch(investorProfilePda);
+    assert.ok(investorProfile.investmentCount.eq(positionIndex.addn(1)));
+    assert.equal(investorProfile.lifetimeInvested.toNumber(), 50_000_000);
   });
 
   it("Releases funds from escrow", async () => {
-    const destinationTokenAccount = (await token.getOrCreateAssociatedTokenAccount(
-      provider.connection,
-      provider.wallet as any,
-      USDC_MINT,
-      new PublicKey("11111111111111111111111111111112") // dummy startup wallet
-    )).address;
+    // Startup was registered with the admin as its payout wallet
+    const destinationTokenAccount = adminTokenAccount;
+
+    const [startupPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("startup"), STARTUP_ID.toBuffer()],
+      program.programId
+    );
 
     const [escrowPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("escrow_authority")],
@@ -194,30 +717,1478 @@ describe("SaloneVest Investment Program", () => {
       program.programId
     );
 
-    const releaseAmount = new anchor.BN(25_000_000); // 25 USDC
+    // Milestone 0: 50% of the raise once the equipment is purchased
+    const [milestonePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("milestone"), STARTUP_ID.toBuffer(), Buffer.from([0])],
+      program.programId
+    );
 
-    const releaseTx = await program.methods
-      .releaseFunds(STARTUP_ID, releaseAmount)
+    const descriptionHash = Array.from(
+      anchor.utils.sha256.hash("Equipment purchased")
+        .match(/.{2}/g)!
+        .map((byte) => parseInt(byte, 16))
+    );
+
+    await program.methods
+      .createMilestone(
+        STARTUP_ID,
+        descriptionHash,
+        5_000,
+        new anchor.BN(Math.floor(Date.now() / 1000) + 90 * 24 * 60 * 60)
+      )
+      .accounts({
+        admin: admin,
+        config: configPda,
+        startup: startupPda,
+        milestone: milestonePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // The admin is also the release authority after initialization
+    await program.methods
+      .approveMilestone()
       .accounts({
         releaseAuthority: admin,
         escrowState: escrowStatePda,
+        milestone: milestonePda,
+        startup: startupPda,
+      })
+      .rpc();
+
+    // Tranches are a share of the final raise, so close and finalize it first
+    await program.methods
+      .updateStartupStatus(STARTUP_ID, { closed: {} })
+      .accounts({
+        admin: admin,
         config: configPda,
+        startup: startupPda,
+      })
+      .rpc();
+
+    await program.methods
+      .finalizeRaise(STARTUP_ID)
+      .accounts({
+        startup: startupPda,
+      })
+      .rpc();
+
+    // The admin is the sole approver after initialization (1-of-1)
+    const startup = await program.account.startup.fetch(startupPda);
+    const [releaseProposalPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("release_proposal"),
+        STARTUP_ID.toBuffer(),
+        startup.releaseProposalCount.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .proposeRelease(STARTUP_ID, 0)
+      .accounts({
+        proposer: admin,
+        config: configPda,
+        startup: startupPda,
+        releaseProposal: releaseProposalPda,
+        milestone: milestonePda,
+        destinationAta: destinationTokenAccount,
+        mint: USDC_MINT,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Milestone 0 is 50% of the 48.75 USDC net raise
+    const tranche = 24_375_000;
+    const proposal = await program.account.releaseProposal.fetch(releaseProposalPda);
+    assert.equal(proposal.amount.toNumber(), tranche);
+    assert.ok(proposal.destination.equals(destinationTokenAccount));
+    assert.deepEqual(proposal.status, { pending: {} });
+
+    const destinationBalanceBefore = await balanceOf(destinationTokenAccount);
+
+    const releaseTx = await program.methods
+      .releaseFunds(STARTUP_ID)
+      .accounts({
+        executor: admin,
+        escrowState: escrowStatePda,
+        config: configPda,
+        startup: startupPda,
+        acceptedMint: acceptedMintPda,
+        releaseProposal: releaseProposalPda,
+        milestone: milestonePda,
         escrowAta: escrowTokenAccount,
         destinationAta: destinationTokenAccount,
         escrowPda: escrowPda,
-        usdcMint: USDC_MINT,
+        treasuryAta: treasuryTokenAccount,
+        feePda: feePda,
+        mint: USDC_MINT,
         tokenProgram: token.TOKEN_PROGRAM_ID,
       })
-      .rpc();
+      .rpc({ commitment: "confirmed" });
 
     console.log("✅ Funds released");
     console.log("Transaction signature:", releaseTx);
 
-    // Verify escrow state was updated
-    const escrowState = await program.account.escrowState.fetch(escrowStatePda);
-    console.log("Updated Escrow State:", {
-      totalEscrow: escrowState.totalEscrow.toString(),
-      activeInvestments: escrowState.activeInvestments.toString(),
+    // Fees were charged on invest, so the whole tranche reaches the startup
+    assert.equal(
+      (await balanceOf(destinationTokenAccount)) - destinationBalanceBefore,
+      BigInt(tranche)
+    );
+
+    // Verify escrow totals were updated
+    const acceptedMint = await program.account.acceptedMint.fetch(acceptedMintPda);
+    assert.equal(acceptedMint.totalEscrow.toNumber(), 48_750_000 - tranche);
+    assert.equal(acceptedMint.totalFeesCollected.toNumber(), 1_250_000);
+
+    const startupAfter = await program.account.startup.fetch(startupPda);
+    assert.deepEqual(startupAfter.status, { funded: {} });
+    assert.equal(startupAfter.totalReleased.toNumber(), tranche);
+
+    const milestone = await program.account.milestone.fetch(milestonePda);
+    assert.deepEqual(milestone.state, { released: {} });
+    const executed = await program.account.releaseProposal.fetch(releaseProposalPda);
+    assert.deepEqual(executed.status, { executed: {} });
+
+    const released = await eventOf(releaseTx, "fundsReleased");
+    assert.ok(released.startupId.equals(STARTUP_ID));
+    assert.ok(released.proposalId.eq(startup.releaseProposalCount));
+    assert.equal(released.milestoneIndex, 0);
+    assert.ok(released.destination.equals(destinationTokenAccount));
+    assert.equal(released.amount.toNumber(), tranche);
+    assert.equal(released.fee.toNumber(), 0);
+    assert.equal(released.startupEscrowBalance.toNumber(), 48_750_000 - tranche);
+    assert.ok(released.mint.equals(USDC_MINT));
+    assert.equal(released.totalEscrow.toNumber(), 48_750_000 - tranche);
+    assert.ok(released.executor.equals(admin));
+  });
+
+  it("Frees a rejected milestone's share of the raise", async () => {
+    const configPda = pda(Buffer.from("config"));
+    const startupPda = pda(Buffer.from("startup"), STARTUP_ID.toBuffer());
+    const milestoneAt = (index: number) =>
+      pda(Buffer.from("milestone"), STARTUP_ID.toBuffer(), Buffer.from([index]));
+    const createMilestone = (index: number, percentBps: number) =>
+      program.methods
+        .createMilestone(
+          STARTUP_ID,
+          Array.from(Buffer.alloc(32, index)),
+          percentBps,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 90 * 24 * 60 * 60)
+        )
+        .accounts({
+          admin: admin,
+          config: configPda,
+          startup: startupPda,
+          milestone: milestoneAt(index),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // Milestone 0 holds 50%; milestone 1 takes the other half
+    await createMilestone(1, 5_000);
+    await assert.rejects(createMilestone(2, 5_000), /InvalidMilestonePercent/);
+
+    await program.methods
+      .rejectMilestone()
+      .accounts({
+        releaseAuthority: admin,
+        escrowState: pda(Buffer.from("escrow")),
+        milestone: milestoneAt(1),
+        startup: startupPda,
+      })
+      .rpc();
+
+    const rejected = await program.account.milestone.fetch(milestoneAt(1));
+    assert.deepEqual(rejected.state, { rejected: {} });
+    assert.equal((await program.account.startup.fetch(startupPda)).milestoneBpsTotal, 5_000);
+
+    // The rejected half can be put towards a new milestone
+    await createMilestone(2, 5_000);
+    const startup = await program.account.startup.fetch(startupPda);
+    assert.equal(startup.milestoneBpsTotal, 10_000);
+    assert.equal(startup.milestoneCount, 3);
+  });
+
+  it("Distributes returns pro-rata to investors", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    const [startupPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("startup"), STARTUP_ID.toBuffer()],
+      program.programId
+    );
+    const [distributionVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("distribution_vault"), STARTUP_ID.toBuffer()],
+      program.programId
+    );
+    const [escrowPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow_authority")],
+      program.programId
+    );
+    const [investmentPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("investment"),
+        investor.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .depositReturns(STARTUP_ID, new anchor.BN(5_000_000)) // 5 USDC
+      .accounts({
+        depositor: admin,
+        config: configPda,
+        startup: startupPda,
+        depositorTokenAccount: adminTokenAccount,
+        distributionVault: distributionVaultPda,
+        escrowPda: escrowPda,
+        mint: USDC_MINT,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const startup = await program.account.startup.fetch(startupPda);
+    assert.equal(startup.totalDistributed.toNumber(), 5_000_000);
+    assert.equal(await balanceOf(distributionVaultPda), BigInt(5_000_000));
+
+    const investorBalanceBefore = await balanceOf(investorTokenAccount);
+
+    await program.methods
+      .claimReturns()
+      .accounts({
+        investor: investor.publicKey,
+        investmentRecord: investmentPda,
+        startup: startupPda,
+        config: configPda,
+        distributionVault: distributionVaultPda,
+        investorTokenAccount: investorTokenAccount,
+        escrowPda: escrowPda,
+        mint: USDC_MINT,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      })
+      .signers([investor])
+      .rpc();
+
+    // The investor holds every active share, so receives the whole deposit
+    // less at most one base unit of rounding in the reward-per-share index
+    const investmentRecord = await program.account.investmentAccount.fetch(investmentPda);
+    const claimed = investmentRecord.returnsClaimed.toNumber();
+    assert.ok(claimed >= 4_999_999 && claimed <= 5_000_000);
+    assert.equal(investmentRecord.unclaimedReturns.toNumber(), 0);
+    assert.equal(
+      (await balanceOf(investorTokenAccount)) - investorBalanceBefore,
+      BigInt(claimed)
+    );
+  });
+
+  it("Withdraws platform fees from the treasury", async () => {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    const treasury = await token.getAccount(provider.connection, treasuryTokenAccount);
+
+    await program.methods
+      .withdrawFees(new anchor.BN(treasury.amount.toString()))
+      .accounts({
+        admin: admin,
+        config: configPda,
+        treasuryAta: treasuryTokenAccount,
+        destinationAta: adminTokenAccount,
+        feePda: feePda,
+        mint: USDC_MINT,
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    assert.equal(await balanceOf(treasuryTokenAccount), BigInt(0));
+  });
+
+  describe("Cancellation", () => {
+    // A separate raise, so cancellations don't disturb the funded startup above
+    const CANCEL_STARTUP_ID = Keypair.generate().publicKey;
+    const startupPda = pda(Buffer.from("startup"), CANCEL_STARTUP_ID.toBuffer());
+    const shareMint = pda(Buffer.from("share_mint"), CANCEL_STARTUP_ID.toBuffer());
+
+    // 10 USDC less the 2.5% platform fee, which is not refunded
+    const amount = 10_000_000;
+    const principal = 9_750_000;
+
+    let lateInvestmentPda: PublicKey;
+
+    const setCoolingOffPeriod = (seconds: number) =>
+      program.methods
+        .updateConfig(null, null, null, null, new anchor.BN(seconds), null, null, null, null, null)
+        .accounts({
+          admin: admin,
+          config: pda(Buffer.from("config")),
+          escrowState: pda(Buffer.from("escrow")),
+        })
+        .rpc();
+
+    before(async () => {
+      await registerStartup(CANCEL_STARTUP_ID, "Cancellable");
+    });
+
+    it("Refunds the principal when the investor cancels inside the cooling-off window", async () => {
+      const investmentPda = await invest(CANCEL_STARTUP_ID, amount);
+
+      const investorBalanceBefore = await balanceOf(investorTokenAccount);
+      const escrowBalanceBefore = await balanceOf(escrowTokenAccount);
+      const acceptedMintBefore = await program.account.acceptedMint.fetch(acceptedMintPda);
+      const escrowStateBefore = await program.account.escrowState.fetch(pda(Buffer.from("escrow")));
+
+      await program.methods
+        .cancelInvestment()
+        .accounts(cancelAccounts(investor.publicKey, investmentPda, CANCEL_STARTUP_ID))
+        .signers([investor])
+        .rpc();
+
+      const record = await program.account.investmentAccount.fetch(investmentPda);
+      assert.deepEqual(record.status, { cancelled: {} });
+
+      assert.equal((await balanceOf(investorTokenAccount)) - investorBalanceBefore, BigInt(principal));
+      assert.equal(escrowBalanceBefore - (await balanceOf(escrowTokenAccount)), BigInt(principal));
+      assert.equal(await shareBalanceOf(shareMint, investor.publicKey), BigInt(0));
+
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.equal(startup.totalRaised.toNumber(), principal);
+      assert.equal(startup.totalRefunded.toNumber(), principal);
+      assert.equal(startup.activeShares.toNumber(), 0);
+
+      const acceptedMint = await program.account.acceptedMint.fetch(acceptedMintPda);
+      assert.equal(acceptedMintBefore.totalEscrow.sub(acceptedMint.totalEscrow).toNumber(), principal);
+      const escrowState = await program.account.escrowState.fetch(pda(Buffer.from("escrow")));
+      assert.equal(
+        escrowStateBefore.activeInvestments.sub(escrowState.activeInvestments).toNumber(),
+        1
+      );
+    });
+
+    it("Frees room under the hard cap when an investment is cancelled", async () => {
+      // The cap leaves room for exactly one position
+      const cappedStartupId = Keypair.generate().publicKey;
+      await registerStartup(cappedStartupId, "Capped", { hardCap: principal });
+
+      const cancelledPda = await invest(cappedStartupId, amount);
+      await program.methods
+        .cancelInvestment()
+        .accounts(cancelAccounts(investor.publicKey, cancelledPda, cappedStartupId))
+        .signers([investor])
+        .rpc();
+
+      // The refunded principal no longer counts against the cap
+      await invest(cappedStartupId, amount);
+      const startup = await program.account.startup.fetch(
+        pda(Buffer.from("startup"), cappedStartupId.toBuffer())
+      );
+      assert.equal(startup.totalRaised.toNumber(), 2 * principal);
+      assert.equal(startup.totalRefunded.toNumber(), principal);
+
+      await assert.rejects(invest(cappedStartupId, amount), /HardCapExceeded/);
+    });
+
+    it("Refunds an investment in full when the raise fails", async () => {
+      // One 10 USDC position cannot meet a 100 USDC soft cap
+      const failedStartupId = Keypair.generate().publicKey;
+      const failedStartupPda = pda(Buffer.from("startup"), failedStartupId.toBuffer());
+      await registerStartup(failedStartupId, "Underfunded", { fundingTarget: 100_000_000 });
+      const investmentPda = await invest(failedStartupId, amount);
+
+      await program.methods
+        .updateStartupStatus(failedStartupId, { closed: {} })
+        .accounts({ admin: admin, config: pda(Buffer.from("config")), startup: failedStartupPda })
+        .rpc();
+      await program.methods.finalizeRaise(failedStartupId).accounts({ startup: failedStartupPda }).rpc();
+
+      const investorBalanceBefore = await balanceOf(investorTokenAccount);
+
+      // Anyone may trigger the refund; the principal always goes to the investor
+      const refundTx = await program.methods
+        .refundInvestment()
+        .accounts(cancelAccounts(admin, investmentPda, failedStartupId))
+        .rpc({ commitment: "confirmed" });
+
+      const record = await program.account.investmentAccount.fetch(investmentPda);
+      assert.deepEqual(record.status, { refunded: {} });
+      assert.equal((await balanceOf(investorTokenAccount)) - investorBalanceBefore, BigInt(principal));
+
+      const acceptedMint = await program.account.acceptedMint.fetch(acceptedMintPda);
+      const refunded = await eventOf(refundTx, "investmentRefunded");
+      assert.ok(refunded.investor.equals(investor.publicKey));
+      assert.ok(refunded.startupId.equals(failedStartupId));
+      assert.ok(refunded.investmentRecord.equals(investmentPda));
+      assert.ok(refunded.refundedBy.equals(admin));
+      assert.equal(refunded.refunded.toNumber(), principal);
+      assert.ok(refunded.totalEscrow.eq(acceptedMint.totalEscrow));
+    });
+
+    it("Rejects a cooling-off cancel once the raise is funded", async () => {
+      // The first position is still inside its 48-hour window, but its raise is
+      // funded and part of the escrow has been released
+      const fundedPositionPda = pda(
+        Buffer.from("investment"),
+        investor.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      );
+
+      await assert.rejects(
+        program.methods
+          .cancelInvestment()
+          .accounts(cancelAccounts(investor.publicKey, fundedPositionPda, STARTUP_ID))
+          .signers([investor])
+          .rpc(),
+        /CancellationNotAllowed/
+      );
+
+      const record = await program.account.investmentAccount.fetch(fundedPositionPda);
+      assert.deepEqual(record.status, { active: {} });
+    });
+
+    it("Rejects an investor cancel after the cooling-off window", async () => {
+      await setCoolingOffPeriod(0);
+      try {
+        lateInvestmentPda = await invest(CANCEL_STARTUP_ID, amount);
+        // Let the (zero-length) window pass
+        await sleep(2_000);
+
+        const investorBalanceBefore = await balanceOf(investorTokenAccount);
+        await assert.rejects(
+          program.methods
+            .cancelInvestment()
+            .accounts(cancelAccounts(investor.publicKey, lateInvestmentPda, CANCEL_STARTUP_ID))
+            .signers([investor])
+            .rpc(),
+          /CancellationNotAllowed/
+        );
+
+        const record = await program.account.investmentAccount.fetch(lateInvestmentPda);
+        assert.deepEqual(record.status, { active: {} });
+        assert.equal(await balanceOf(investorTokenAccount), investorBalanceBefore);
+        assert.equal(await shareBalanceOf(shareMint, investor.publicKey), BigInt(principal));
+      } finally {
+        await setCoolingOffPeriod(48 * 60 * 60);
+      }
+    });
+
+    it("Allows a cancel outside the window once the startup is cancelled", async () => {
+      await program.methods
+        .updateStartupStatus(CANCEL_STARTUP_ID, { cancelled: {} })
+        .accounts({
+          admin: admin,
+          config: pda(Buffer.from("config")),
+          startup: startupPda,
+        })
+        .rpc();
+
+      const investorBalanceBefore = await balanceOf(investorTokenAccount);
+
+      await program.methods
+        .cancelInvestment()
+        .accounts(cancelAccounts(investor.publicKey, lateInvestmentPda, CANCEL_STARTUP_ID))
+        .signers([investor])
+        .rpc();
+
+      const record = await program.account.investmentAccount.fetch(lateInvestmentPda);
+      assert.deepEqual(record.status, { cancelled: {} });
+      assert.equal((await balanceOf(investorTokenAccount)) - investorBalanceBefore, BigInt(principal));
+      assert.equal(await shareBalanceOf(shareMint, investor.publicKey), BigInt(0));
+
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.deepEqual(startup.status, { cancelled: {} });
+      assert.equal(startup.totalRefunded.toNumber(), 2 * principal);
+      assert.equal(startup.activeShares.toNumber(), 0);
+    });
+
+    it("Refunds pro-rata when a funded startup is cancelled after a release", async () => {
+      const fundedStartupPda = pda(Buffer.from("startup"), STARTUP_ID.toBuffer());
+      const fundedPositionPda = pda(
+        Buffer.from("investment"),
+        investor.publicKey.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      );
+
+      await program.methods
+        .updateStartupStatus(STARTUP_ID, { cancelled: {} })
+        .accounts({
+          admin: admin,
+          config: pda(Buffer.from("config")),
+          startup: fundedStartupPda,
+        })
+        .rpc();
+
+      // Half of the 48.75 USDC raise was released before the cancellation
+      const startupBefore = await program.account.startup.fetch(fundedStartupPda);
+      assert.equal(startupBefore.cancelledEscrowBalance.toNumber(), 24_375_000);
+      assert.equal(startupBefore.cancelledNetRaised.toNumber(), 48_750_000);
+
+      const investorBalanceBefore = await balanceOf(investorTokenAccount);
+
+      await program.methods
+        .cancelInvestment()
+        .accounts(cancelAccounts(investor.publicKey, fundedPositionPda, STARTUP_ID))
+        .signers([investor])
+        .rpc();
+
+      // The position gets the same fraction of its principal as every other would
+      const record = await program.account.investmentAccount.fetch(fundedPositionPda);
+      assert.deepEqual(record.status, { cancelled: {} });
+      assert.equal((await balanceOf(investorTokenAccount)) - investorBalanceBefore, BigInt(24_375_000));
+
+      const startup = await program.account.startup.fetch(fundedStartupPda);
+      assert.equal(startup.totalRefunded.toNumber(), 24_375_000);
+    });
+  });
+
+  describe("Emergency pause", () => {
+    const PAUSE_STARTUP_ID = Keypair.generate().publicKey;
+    const startupPda = pda(Buffer.from("startup"), PAUSE_STARTUP_ID.toBuffer());
+    const configPda = pda(Buffer.from("config"));
+    const escrowStatePda = pda(Buffer.from("escrow"));
+    const escrowPda = pda(Buffer.from("escrow_authority"));
+    const shareMint = pda(Buffer.from("share_mint"), PAUSE_STARTUP_ID.toBuffer());
+
+    // Flag values of ProgramConfig::PAUSE_*
+    const PAUSE_DEPOSITS = 1 << 0;
+    const PAUSE_RELEASES = 1 << 1;
+    const PAUSE_REFUNDS = 1 << 2;
+    const PAUSE_DISTRIBUTIONS = 1 << 3;
+    const PAUSE_TRANSFERS = 1 << 4;
+
+    let depositPositionPda: PublicKey;
+    let releasePositionPda: PublicKey;
+
+    const setPaused = async (paused: boolean, flags: number) => {
+      const method = paused ? program.methods.pause(flags) : program.methods.unpause(flags);
+      await method.accounts({ authority: admin, config: configPda }).rpc();
+
+      const config = await program.account.programConfig.fetch(configPda);
+      assert.equal(config.paused & flags, paused ? flags : 0);
+    };
+
+    // Run `instruction` while `flags` are paused and expect ProgramPaused, then
+    // unpause and run it again
+    const blockedWhilePaused = async (flags: number, instruction: () => Promise<unknown>) => {
+      await setPaused(true, flags);
+      try {
+        await assert.rejects(instruction(), /ProgramPaused/);
+      } finally {
+        await setPaused(false, flags);
+      }
+      await instruction();
+    };
+
+    before(async () => {
+      await registerStartup(PAUSE_STARTUP_ID, "Pausable");
+      await attest(receiver.publicKey);
+    });
+
+    it("PAUSE_DEPOSITS blocks invest_usd until unpaused", async () => {
+      const escrowBalanceBefore = await balanceOf(escrowTokenAccount);
+      const positions = [];
+      await blockedWhilePaused(PAUSE_DEPOSITS, async () => {
+        positions.push(await invest(PAUSE_STARTUP_ID, 10_000_000));
+      });
+
+      // Only the unpaused attempt moved funds
+      depositPositionPda = positions[positions.length - 1];
+      const record = await program.account.investmentAccount.fetch(depositPositionPda);
+      assert.deepEqual(record.status, { active: {} });
+      assert.equal((await balanceOf(escrowTokenAccount)) - escrowBalanceBefore, BigInt(9_750_000));
+    });
+
+    it("PAUSE_DISTRIBUTIONS blocks deposit_returns until unpaused", async () => {
+      const distributionVaultPda = pda(Buffer.from("distribution_vault"), PAUSE_STARTUP_ID.toBuffer());
+
+      await blockedWhilePaused(PAUSE_DISTRIBUTIONS, () =>
+        program.methods
+          .depositReturns(PAUSE_STARTUP_ID, new anchor.BN(1_000_000))
+          .accounts({
+            depositor: admin,
+            config: configPda,
+            startup: startupPda,
+            depositorTokenAccount: adminTokenAccount,
+            distributionVault: distributionVaultPda,
+            escrowPda: escrowPda,
+            mint: USDC_MINT,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc()
+      );
+
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.equal(startup.totalDistributed.toNumber(), 1_000_000);
+      assert.equal(await balanceOf(distributionVaultPda), BigInt(1_000_000));
+    });
+
+    it("PAUSE_REFUNDS blocks cancel_investment until unpaused", async () => {
+      const investorBalanceBefore = await balanceOf(investorTokenAccount);
+
+      await blockedWhilePaused(PAUSE_REFUNDS, () =>
+        program.methods
+          .cancelInvestment()
+          .accounts(cancelAccounts(investor.publicKey, depositPositionPda, PAUSE_STARTUP_ID))
+          .signers([investor])
+          .rpc()
+      );
+
+      const record = await program.account.investmentAccount.fetch(depositPositionPda);
+      assert.deepEqual(record.status, { cancelled: {} });
+      assert.equal((await balanceOf(investorTokenAccount)) - investorBalanceBefore, BigInt(9_750_000));
+    });
+
+    it("PAUSE_RELEASES blocks release_funds until unpaused", async () => {
+      releasePositionPda = await invest(PAUSE_STARTUP_ID, 10_000_000);
+
+      // Fund the raise and approve a milestone for the whole of it
+      const milestonePda = pda(
+        Buffer.from("milestone"),
+        PAUSE_STARTUP_ID.toBuffer(),
+        Buffer.from([0])
+      );
+      await program.methods
+        .createMilestone(
+          PAUSE_STARTUP_ID,
+          Array.from(Buffer.alloc(32, 1)),
+          10_000,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 90 * 24 * 60 * 60)
+        )
+        .accounts({
+          admin: admin,
+          config: configPda,
+          startup: startupPda,
+          milestone: milestonePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      await program.methods
+        .approveMilestone()
+        .accounts({
+          releaseAuthority: admin,
+          escrowState: escrowStatePda,
+          milestone: milestonePda,
+          startup: startupPda,
+        })
+        .rpc();
+      await program.methods
+        .updateStartupStatus(PAUSE_STARTUP_ID, { closed: {} })
+        .accounts({ admin: admin, config: configPda, startup: startupPda })
+        .rpc();
+      await program.methods.finalizeRaise(PAUSE_STARTUP_ID).accounts({ startup: startupPda }).rpc();
+
+      const releaseProposalPda = pda(
+        Buffer.from("release_proposal"),
+        PAUSE_STARTUP_ID.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      );
+      await program.methods
+        .proposeRelease(PAUSE_STARTUP_ID, 0)
+        .accounts({
+          proposer: admin,
+          config: configPda,
+          startup: startupPda,
+          releaseProposal: releaseProposalPda,
+          milestone: milestonePda,
+          destinationAta: adminTokenAccount,
+          mint: USDC_MINT,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const payoutBalanceBefore = await balanceOf(adminTokenAccount);
+
+      await blockedWhilePaused(PAUSE_RELEASES, () =>
+        program.methods
+          .releaseFunds(PAUSE_STARTUP_ID)
+          .accounts({
+            executor: admin,
+            escrowState: escrowStatePda,
+            config: configPda,
+            startup: startupPda,
+            acceptedMint: acceptedMintPda,
+            releaseProposal: releaseProposalPda,
+            milestone: milestonePda,
+            escrowAta: escrowTokenAccount,
+            destinationAta: adminTokenAccount,
+            escrowPda: escrowPda,
+            treasuryAta: treasuryTokenAccount,
+            feePda: feePda,
+            mint: USDC_MINT,
+            tokenProgram: token.TOKEN_PROGRAM_ID,
+          })
+          .rpc()
+      );
+
+      const proposal = await program.account.releaseProposal.fetch(releaseProposalPda);
+      assert.deepEqual(proposal.status, { executed: {} });
+      assert.equal((await balanceOf(adminTokenAccount)) - payoutBalanceBefore, BigInt(9_750_000));
+    });
+
+    it("PAUSE_TRANSFERS blocks transfer_position until unpaused", async () => {
+      const receiverProfilePda = pda(Buffer.from("investor"), receiver.publicKey.toBuffer());
+
+      await blockedWhilePaused(PAUSE_TRANSFERS, async () => {
+        const profile = await program.account.investorProfile.fetchNullable(receiverProfilePda);
+        const positionIndex = profile ? profile.investmentCount : new anchor.BN(0);
+        await program.methods
+          .transferPosition()
+          .accounts({
+            owner: investor.publicKey,
+            receiver: receiver.publicKey,
+            investmentRecord: releasePositionPda,
+            receiverProfile: receiverProfilePda,
+            receiverAttestation: pda(Buffer.from("attestation"), receiver.publicKey.toBuffer()),
+            newInvestmentRecord: pda(
+              Buffer.from("investment"),
+              receiver.publicKey.toBuffer(),
+              positionIndex.toArrayLike(Buffer, "le", 8)
+            ),
+            startup: startupPda,
+            config: configPda,
+            shareMint: shareMint,
+            ownerShareAccount: shareAccountOf(shareMint, investor.publicKey),
+            receiverShareAccount: shareAccountOf(shareMint, receiver.publicKey),
+            shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([investor])
+          .rpc();
+      });
+
+      const record = await program.account.investmentAccount.fetch(releasePositionPda);
+      assert.deepEqual(record.status, { transferred: {} });
+      assert.equal(await shareBalanceOf(shareMint, receiver.publicKey), BigInt(9_750_000));
+    });
+  });
+
+  describe("Position transfer", () => {
+    const TRANSFER_STARTUP_ID = Keypair.generate().publicKey;
+    const startupPda = pda(Buffer.from("startup"), TRANSFER_STARTUP_ID.toBuffer());
+    const configPda = pda(Buffer.from("config"));
+    const escrowPda = pda(Buffer.from("escrow_authority"));
+    const shareMint = pda(Buffer.from("share_mint"), TRANSFER_STARTUP_ID.toBuffer());
+    const distributionVaultPda = pda(Buffer.from("distribution_vault"), TRANSFER_STARTUP_ID.toBuffer());
+    const receiverProfilePda = pda(Buffer.from("investor"), receiver.publicKey.toBuffer());
+
+    let positionPda: PublicKey;
+    let movedPositionPda: PublicKey;
+
+    const depositReturns = (amount: number) =>
+      program.methods
+        .depositReturns(TRANSFER_STARTUP_ID, new anchor.BN(amount))
+        .accounts({
+          depositor: admin,
+          config: configPda,
+          startup: startupPda,
+          depositorTokenAccount: adminTokenAccount,
+          distributionVault: distributionVaultPda,
+          escrowPda: escrowPda,
+          mint: USDC_MINT,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const claimReturns = (holder: Keypair, investmentPda: PublicKey, holderTokenAccount: PublicKey) =>
+      program.methods
+        .claimReturns()
+        .accounts({
+          investor: holder.publicKey,
+          investmentRecord: investmentPda,
+          startup: startupPda,
+          config: configPda,
+          distributionVault: distributionVaultPda,
+          investorTokenAccount: holderTokenAccount,
+          escrowPda: escrowPda,
+          mint: USDC_MINT,
+          tokenProgram: token.TOKEN_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+
+    // Accounts for transferring `investmentPda` from `owner` to `to`, at `to`'s next position index
+    const transferAccounts = async (owner: PublicKey, to: PublicKey, investmentPda: PublicKey) => {
+      const toProfilePda = pda(Buffer.from("investor"), to.toBuffer());
+      const profile = await program.account.investorProfile.fetchNullable(toProfilePda);
+      const positionIndex = profile ? profile.investmentCount : new anchor.BN(0);
+      return {
+        owner: owner,
+        receiver: to,
+        investmentRecord: investmentPda,
+        receiverProfile: toProfilePda,
+        receiverAttestation: pda(Buffer.from("attestation"), to.toBuffer()),
+        newInvestmentRecord: pda(
+          Buffer.from("investment"),
+          to.toBuffer(),
+          positionIndex.toArrayLike(Buffer, "le", 8)
+        ),
+        startup: startupPda,
+        config: configPda,
+        shareMint: shareMint,
+        ownerShareAccount: shareAccountOf(shareMint, owner),
+        receiverShareAccount: shareAccountOf(shareMint, to),
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+    };
+
+    before(async () => {
+      await registerStartup(TRANSFER_STARTUP_ID, "Transferable");
+      const receiverAttestationPda = pda(Buffer.from("attestation"), receiver.publicKey.toBuffer());
+      if (!(await program.account.investorAttestation.fetchNullable(receiverAttestationPda))) {
+        await attest(receiver.publicKey);
+      }
+
+      // One claimed and one pending distribution on the position before it moves
+      positionPda = await invest(TRANSFER_STARTUP_ID, 10_000_000);
+      await depositReturns(2_000_000);
+      await claimReturns(investor, positionPda, investorTokenAccount);
+      await depositReturns(1_000_000);
+    });
+
+    it("Transfers a position with only the owner's signature", async () => {
+      const accounts = await transferAccounts(investor.publicKey, receiver.publicKey, positionPda);
+      movedPositionPda = accounts.newInvestmentRecord;
+      const before = await program.account.investmentAccount.fetch(positionPda);
+      const receiverLamportsBefore = await provider.connection.getBalance(receiver.publicKey);
+
+      await program.methods.transferPosition().accounts(accounts).signers([investor]).rpc();
+
+      // The owner paid for the receiver's profile, record and share account
+      assert.equal(await provider.connection.getBalance(receiver.publicKey), receiverLamportsBefore);
+
+      const old = await program.account.investmentAccount.fetch(positionPda);
+      assert.deepEqual(old.status, { transferred: {} });
+      assert.equal(old.unclaimedReturns.toNumber(), 0);
+
+      // Terms and accounting carry over to the receiver's record
+      const moved = await program.account.investmentAccount.fetch(accounts.newInvestmentRecord);
+      assert.ok(moved.investor.equals(receiver.publicKey));
+      assert.ok(moved.startupId.equals(TRANSFER_STARTUP_ID));
+      assert.deepEqual(moved.status, { active: {} });
+      assert.equal(moved.principalUsd.toNumber(), before.principalUsd.toNumber());
+      assert.equal(moved.shares.toNumber(), before.shares.toNumber());
+      assert.equal(moved.investmentDate.toNumber(), before.investmentDate.toNumber());
+      assert.equal(moved.expectedReturn, before.expectedReturn);
+      assert.ok(moved.rewardDebt.eq(before.rewardDebt));
+      assert.equal(moved.unclaimedReturns.toNumber(), before.unclaimedReturns.toNumber());
+      assert.ok(moved.returnsClaimed.toNumber() >= 1_999_999);
+      assert.equal(moved.returnsClaimed.toNumber(), before.returnsClaimed.toNumber());
+      assert.equal(moved.settledAmount.toNumber(), before.settledAmount.toNumber());
+      assert.equal(moved.shortfall.toNumber(), before.shortfall.toNumber());
+
+      // Shares moved with the position and stay frozen
+      assert.equal(await shareBalanceOf(shareMint, investor.publicKey), BigInt(0));
+      assert.equal(await shareBalanceOf(shareMint, receiver.publicKey), BigInt(9_750_000));
+      const receiverShares = await token.getAccount(
+        provider.connection,
+        accounts.receiverShareAccount,
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      assert.equal(receiverShares.isFrozen, true);
+
+      // The distribution pending at transfer time is now the receiver's to claim
+      const receiverTokenAccount = (await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        receiver,
+        USDC_MINT,
+        receiver.publicKey
+      )).address;
+      await claimReturns(receiver, accounts.newInvestmentRecord, receiverTokenAccount);
+      const claimed = await balanceOf(receiverTokenAccount);
+      assert.ok(claimed >= BigInt(999_999) && claimed <= BigInt(1_000_000));
+    });
+
+    it("Rejects a transfer not signed by the position's owner", async () => {
+      // The previous owner cannot pull the position back without the new holder
+      await assert.rejects(
+        program.methods
+          .transferPosition()
+          .accounts(await transferAccounts(investor.publicKey, investor.publicKey, movedPositionPda))
+          .signers([investor])
+          .rpc(),
+        /Unauthorized/
+      );
+
+      const moved = await program.account.investmentAccount.fetch(movedPositionPda);
+      assert.ok(moved.investor.equals(receiver.publicKey));
+      assert.deepEqual(moved.status, { active: {} });
+    });
+
+    it("Closes finished records and refunds the rent to whoever paid it", async () => {
+      const closeInvestment = (holder: Keypair, investmentPda: PublicKey, rentPayer: PublicKey) =>
+        program.methods
+          .closeInvestment()
+          .accounts({ investor: holder.publicKey, rentPayer: rentPayer, investmentRecord: investmentPda })
+          .signers([holder])
+          .rpc({ commitment: "confirmed" });
+
+      // An active position cannot be closed
+      await assert.rejects(
+        closeInvestment(receiver, movedPositionPda, investor.publicKey),
+        /InvestmentNotTerminal/
+      );
+
+      // Hand the position back; the investor paid the rent on the record the receiver leaves behind
+      const accounts = await transferAccounts(receiver.publicKey, investor.publicKey, movedPositionPda);
+      await program.methods.transferPosition().accounts(accounts).signers([receiver]).rpc();
+
+      const moved = await program.account.investmentAccount.fetch(movedPositionPda);
+      assert.deepEqual(moved.status, { transferred: {} });
+      assert.ok(moved.rentPayer.equals(investor.publicKey));
+
+      // The rent cannot be redirected to the closing holder
+      await assert.rejects(
+        closeInvestment(receiver, movedPositionPda, receiver.publicKey),
+        /ConstraintAddress/
+      );
+
+      const rent = await provider.connection.getBalance(movedPositionPda);
+      const investorLamportsBefore = await provider.connection.getBalance(investor.publicKey);
+      const receiverLamportsBefore = await provider.connection.getBalance(receiver.publicKey);
+
+      await closeInvestment(receiver, movedPositionPda, investor.publicKey);
+
+      assert.equal(await program.account.investmentAccount.fetchNullable(movedPositionPda), null);
+      assert.equal(
+        await provider.connection.getBalance(investor.publicKey),
+        investorLamportsBefore + rent
+      );
+      assert.equal(await provider.connection.getBalance(receiver.publicKey), receiverLamportsBefore);
+
+      // The investor's original record was paid for by the investor
+      const original = await program.account.investmentAccount.fetch(positionPda);
+      assert.ok(original.rentPayer.equals(investor.publicKey));
+      const closeTx = await closeInvestment(investor, positionPda, investor.publicKey);
+      assert.equal(await program.account.investmentAccount.fetchNullable(positionPda), null);
+
+      // The event keeps a summary of the record that is gone
+      const closed = await eventOf(closeTx, "investmentClosed");
+      assert.ok(closed.investor.equals(investor.publicKey));
+      assert.ok(closed.startupId.equals(original.startupId));
+      assert.ok(closed.investmentRecord.equals(positionPda));
+      assert.ok(closed.positionIndex.eq(original.positionIndex));
+      assert.ok(closed.mint.equals(USDC_MINT));
+      assert.deepEqual(closed.status, { transferred: {} });
+      assert.ok(closed.grossAmount.eq(original.grossUsd));
+      assert.ok(closed.principal.eq(original.principalUsd));
+      assert.ok(closed.investmentDate.eq(original.investmentDate));
+    });
+  });
+
+  describe("Secondary market", () => {
+    const MARKET_STARTUP_ID = Keypair.generate().publicKey;
+    // Raise that is cancelled while its position is listed
+    const CANCELLED_STARTUP_ID = Keypair.generate().publicKey;
+    const configPda = pda(Buffer.from("config"));
+    const escrowStatePda = pda(Buffer.from("escrow"));
+
+    // 2 USDC per whole share, 5% royalty to the startup, 2.5% platform fee
+    const PRICE_PER_SHARE = 2_000_000;
+    const ROYALTY_BPS = 500;
+
+    let receiverTokenAccount: PublicKey;
+    let positionPda: PublicKey;
+
+    const listingOf = (investmentPda: PublicKey) => pda(Buffer.from("listing"), investmentPda.toBuffer());
+
+    const listPosition = (investmentPda: PublicKey, shares: number) =>
+      program.methods
+        .listPosition(
+          new anchor.BN(shares),
+          new anchor.BN(PRICE_PER_SHARE),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 24 * 60 * 60)
+        )
+        .accounts({
+          seller: investor.publicKey,
+          investmentRecord: investmentPda,
+          listing: listingOf(investmentPda),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([investor])
+        .rpc();
+
+    // Accounts for the receiver buying from the investor's listing on `investmentPda`
+    const fillAccounts = async (startupId: PublicKey, investmentPda: PublicKey) => {
+      const buyerProfilePda = pda(Buffer.from("investor"), receiver.publicKey.toBuffer());
+      const profile = await program.account.investorProfile.fetchNullable(buyerProfilePda);
+      const positionIndex = profile ? profile.investmentCount : new anchor.BN(0);
+      const shareMint = pda(Buffer.from("share_mint"), startupId.toBuffer());
+      return {
+        buyer: receiver.publicKey,
+        seller: investor.publicKey,
+        listing: listingOf(investmentPda),
+        investmentRecord: investmentPda,
+        buyerProfile: buyerProfilePda,
+        buyerAttestation: pda(Buffer.from("attestation"), receiver.publicKey.toBuffer()),
+        newInvestmentRecord: pda(
+          Buffer.from("investment"),
+          receiver.publicKey.toBuffer(),
+          positionIndex.toArrayLike(Buffer, "le", 8)
+        ),
+        startup: pda(Buffer.from("startup"), startupId.toBuffer()),
+        acceptedMint: acceptedMintPda,
+        config: configPda,
+        escrowState: escrowStatePda,
+        mint: USDC_MINT,
+        buyerTokenAccount: receiverTokenAccount,
+        sellerTokenAccount: investorTokenAccount,
+        payoutTokenAccount: adminTokenAccount,
+        treasuryAta: treasuryTokenAccount,
+        feePda: feePda,
+        shareMint: shareMint,
+        sellerShareAccount: shareAccountOf(shareMint, investor.publicKey),
+        buyerShareAccount: shareAccountOf(shareMint, receiver.publicKey),
+        tokenProgram: token.TOKEN_PROGRAM_ID,
+        shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+    };
+
+    const fillListing = async (startupId: PublicKey, investmentPda: PublicKey, shares: number) =>
+      program.methods
+        .fillListing(new anchor.BN(shares), new anchor.BN(PRICE_PER_SHARE))
+        .accounts(await fillAccounts(startupId, investmentPda))
+        .signers([receiver])
+        .rpc();
+
+    const setTierLimits = (lifetimeCap: number, annualCap: number) =>
+      program.methods
+        .setTierLimits([{ lifetimeCap: new anchor.BN(lifetimeCap), annualCap: new anchor.BN(annualCap) }])
+        .accounts({ admin: admin, config: configPda })
+        .rpc();
+
+    before(async () => {
+      await registerStartup(MARKET_STARTUP_ID, "Tradable");
+      await program.methods
+        .setStartupRoyalty(MARKET_STARTUP_ID, ROYALTY_BPS)
+        .accounts({
+          admin: admin,
+          config: configPda,
+          startup: pda(Buffer.from("startup"), MARKET_STARTUP_ID.toBuffer()),
+        })
+        .rpc();
+
+      const receiverAttestationPda = pda(Buffer.from("attestation"), receiver.publicKey.toBuffer());
+      if (!(await program.account.investorAttestation.fetchNullable(receiverAttestationPda))) {
+        await attest(receiver.publicKey);
+      }
+
+      // The receiver buys with USDC from the investor
+      receiverTokenAccount = (await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        receiver,
+        USDC_MINT,
+        receiver.publicKey
+      )).address;
+      await token.transfer(
+        provider.connection,
+        investor,
+        investorTokenAccount,
+        receiverTokenAccount,
+        investor,
+        20_000_000
+      );
+
+      // 10 USDC less the platform fee: 9.75 shares
+      positionPda = await invest(MARKET_STARTUP_ID, 10_000_000);
+      await listPosition(positionPda, 9_750_000);
+    });
+
+    it("Fills part of a listing, splitting the cost between seller, startup and treasury", async () => {
+      const accounts = await fillAccounts(MARKET_STARTUP_ID, positionPda);
+      const buyerBefore = await balanceOf(receiverTokenAccount);
+      const sellerBefore = await balanceOf(investorTokenAccount);
+      const payoutBefore = await balanceOf(adminTokenAccount);
+      const treasuryBefore = await balanceOf(treasuryTokenAccount);
+
+      // 4 shares at 2 USDC: 8 USDC, of which 0.4 royalty and 0.2 fee
+      await fillListing(MARKET_STARTUP_ID, positionPda, 4_000_000);
+
+      assert.equal(buyerBefore - (await balanceOf(receiverTokenAccount)), BigInt(8_000_000));
+      assert.equal((await balanceOf(investorTokenAccount)) - sellerBefore, BigInt(7_400_000));
+      assert.equal((await balanceOf(adminTokenAccount)) - payoutBefore, BigInt(400_000));
+      assert.equal((await balanceOf(treasuryTokenAccount)) - treasuryBefore, BigInt(200_000));
+
+      // The listing stays open for the rest
+      const listing = await program.account.listing.fetch(listingOf(positionPda));
+      assert.equal(listing.sharesRemaining.toNumber(), 5_750_000);
+
+      // The seller's position shrinks and the buyer's takes a proportional part of it
+      const sold = await program.account.investmentAccount.fetch(positionPda);
+      assert.deepEqual(sold.status, { active: {} });
+      assert.equal(sold.shares.toNumber(), 5_750_000);
+      assert.equal(sold.principalUsd.toNumber(), 5_750_000);
+
+      const bought = await program.account.investmentAccount.fetch(accounts.newInvestmentRecord);
+      assert.ok(bought.investor.equals(receiver.publicKey));
+      assert.deepEqual(bought.status, { active: {} });
+      assert.equal(bought.shares.toNumber(), 4_000_000);
+      assert.equal(bought.principalUsd.toNumber(), 4_000_000);
+      assert.equal(bought.investmentDate.toNumber(), sold.investmentDate.toNumber());
+
+      const shareMint = pda(Buffer.from("share_mint"), MARKET_STARTUP_ID.toBuffer());
+      assert.equal(await shareBalanceOf(shareMint, investor.publicKey), BigInt(5_750_000));
+      assert.equal(await shareBalanceOf(shareMint, receiver.publicKey), BigInt(4_000_000));
+    });
+
+    it("Rejects a fill that would take the buyer over their annual cap", async () => {
+      const buyerBefore = await balanceOf(receiverTokenAccount);
+
+      // The rest of the listing costs 11.5 USDC; the buyer has spent 8 of a 10 USDC cap
+      await setTierLimits(10_000_000_000, 10_000_000);
+      try {
+        await assert.rejects(
+          fillListing(MARKET_STARTUP_ID, positionPda, 5_750_000),
+          /AnnualCapExceeded/
+        );
+      } finally {
+        await setTierLimits(10_000_000_000, 5_000_000_000);
+      }
+
+      assert.equal(await balanceOf(receiverTokenAccount), buyerBefore);
+      const listing = await program.account.listing.fetch(listingOf(positionPda));
+      assert.equal(listing.sharesRemaining.toNumber(), 5_750_000);
+    });
+
+    it("Rejects a fill once the startup is cancelled", async () => {
+      await registerStartup(CANCELLED_STARTUP_ID, "Cancelled");
+      const cancelledPositionPda = await invest(CANCELLED_STARTUP_ID, 1_000_000);
+      await listPosition(cancelledPositionPda, 975_000);
+      await program.methods
+        .updateStartupStatus(CANCELLED_STARTUP_ID, { cancelled: {} })
+        .accounts({
+          admin: admin,
+          config: configPda,
+          startup: pda(Buffer.from("startup"), CANCELLED_STARTUP_ID.toBuffer()),
+        })
+        .rpc();
+
+      await assert.rejects(
+        fillListing(CANCELLED_STARTUP_ID, cancelledPositionPda, 975_000),
+        /StartupNotTradable/
+      );
+    });
+
+    it("Cancels a listing and returns its rent to the seller", async () => {
+      const listingPda = listingOf(positionPda);
+
+      // Only the seller may withdraw a listing before it expires
+      await assert.rejects(
+        program.methods
+          .cancelListing()
+          .accounts({ authority: receiver.publicKey, seller: investor.publicKey, listing: listingPda })
+          .signers([receiver])
+          .rpc(),
+        /Unauthorized/
+      );
+
+      const rent = await provider.connection.getBalance(listingPda);
+      const sellerLamportsBefore = await provider.connection.getBalance(investor.publicKey);
+
+      await program.methods
+        .cancelListing()
+        .accounts({ authority: investor.publicKey, seller: investor.publicKey, listing: listingPda })
+        .signers([investor])
+        .rpc();
+
+      // The investor signed but the provider paid the transaction fee
+      assert.equal(await program.account.listing.fetchNullable(listingPda), null);
+      assert.equal(
+        await provider.connection.getBalance(investor.publicKey),
+        sellerLamportsBefore + rent
+      );
+
+      // The unsold shares stay with the seller's position
+      const record = await program.account.investmentAccount.fetch(positionPda);
+      assert.deepEqual(record.status, { active: {} });
+      assert.equal(record.shares.toNumber(), 5_750_000);
+    });
+  });
+
+  describe("Token-2022 transfer-fee stablecoin", () => {
+    const FEE_STARTUP_ID = Keypair.generate().publicKey;
+    const startupPda = pda(Buffer.from("startup"), FEE_STARTUP_ID.toBuffer());
+    const shareMint = pda(Buffer.from("share_mint"), FEE_STARTUP_ID.toBuffer());
+    const configPda = pda(Buffer.from("config"));
+    const escrowStatePda = pda(Buffer.from("escrow"));
+    const escrowPda = pda(Buffer.from("escrow_authority"));
+
+    // Localnet `cluster_mints::localnet::FEE_USD`, with a 1% transfer fee
+    const feeMintKeypair = loadKeypair("localnet-fee-usd");
+    const FEE_MINT = feeMintKeypair.publicKey;
+    const TRANSFER_FEE_BPS = 100;
+    const feeAcceptedMintPda = pda(Buffer.from("accepted_mint"), FEE_MINT.toBuffer());
+
+    let investorFeeAccount: PublicKey;
+    let payoutFeeAccount: PublicKey;
+    let escrowFeeAccount: PublicKey;
+    let treasuryFeeAccount: PublicKey;
+    let positionPda: PublicKey;
+
+    // What a recipient is credited after the mint withholds its transfer fee (rounded up)
+    const afterTransferFee = (amount: bigint): bigint =>
+      amount - (amount * BigInt(TRANSFER_FEE_BPS) + BigInt(9_999)) / BigInt(10_000);
+
+    const feeBalanceOf = (account: PublicKey) => balanceOf(account, token.TOKEN_2022_PROGRAM_ID);
+
+    // The allowlist entry tracks exactly what the escrow vault holds
+    const assertEscrowTotalMatchesVault = async () => {
+      const acceptedMint = await program.account.acceptedMint.fetch(feeAcceptedMintPda);
+      assert.equal(BigInt(acceptedMint.totalEscrow.toString()), await feeBalanceOf(escrowFeeAccount));
+    };
+
+    const feeAta = async (owner: PublicKey) =>
+      (await token.getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        (provider.wallet as anchor.Wallet).payer,
+        FEE_MINT,
+        owner,
+        true,
+        undefined,
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      )).address;
+
+    before(async () => {
+      if (!(await provider.connection.getAccountInfo(FEE_MINT))) {
+        const mintLen = token.getMintLen([token.ExtensionType.TransferFeeConfig]);
+        const tx = new anchor.web3.Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: admin,
+            newAccountPubkey: FEE_MINT,
+            space: mintLen,
+            lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+            programId: token.TOKEN_2022_PROGRAM_ID,
+          }),
+          token.createInitializeTransferFeeConfigInstruction(
+            FEE_MINT,
+            admin,
+            admin,
+            TRANSFER_FEE_BPS,
+            BigInt(1_000_000_000),
+            token.TOKEN_2022_PROGRAM_ID
+          ),
+          token.createInitializeMintInstruction(FEE_MINT, 6, admin, null, token.TOKEN_2022_PROGRAM_ID)
+        );
+        await provider.sendAndConfirm(tx, [feeMintKeypair]);
+      }
+
+      investorFeeAccount = await feeAta(investor.publicKey);
+      payoutFeeAccount = await feeAta(admin);
+      escrowFeeAccount = token.getAssociatedTokenAddressSync(
+        FEE_MINT,
+        escrowPda,
+        true,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+      treasuryFeeAccount = await feeAta(feePda);
+      await token.mintTo(
+        provider.connection,
+        (provider.wallet as anchor.Wallet).payer,
+        FEE_MINT,
+        investorFeeAccount,
+        admin,
+        100_000_000,
+        [],
+        undefined,
+        token.TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .addAcceptedMint()
+        .accounts({
+          admin: admin,
+          config: configPda,
+          mint: FEE_MINT,
+          acceptedMint: feeAcceptedMintPda,
+          escrowAta: escrowFeeAccount,
+          escrowPda: escrowPda,
+          tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await registerStartup(FEE_STARTUP_ID, "Fee-bearing", { mint: FEE_MINT });
+    });
+
+    it("Records the principal escrow actually received", async () => {
+      const profile = await program.account.investorProfile.fetch(
+        pda(Buffer.from("investor"), investor.publicKey.toBuffer())
+      );
+      positionPda = pda(
+        Buffer.from("investment"),
+        investor.publicKey.toBuffer(),
+        profile.investmentCount.toArrayLike(Buffer, "le", 8)
+      );
+
+      await program.methods
+        .investUsd(new anchor.BN(10_000_000), FEE_STARTUP_ID)
+        .accounts({
+          investor: investor.publicKey,
+          investorTokenAccount: investorFeeAccount,
+          programEscrowAta: escrowFeeAccount,
+          escrowPda: escrowPda,
+          treasuryAta: treasuryFeeAccount,
+          feePda: feePda,
+          investorProfile: pda(Buffer.from("investor"), investor.publicKey.toBuffer()),
+          attestation: pda(Buffer.from("attestation"), investor.publicKey.toBuffer()),
+          investmentRecord: positionPda,
+          startup: startupPda,
+          acceptedMint: feeAcceptedMintPda,
+          config: configPda,
+          escrowState: escrowStatePda,
+          mint: FEE_MINT,
+          shareMint: shareMint,
+          investorShareAccount: shareAccountOf(shareMint, investor.publicKey),
+          tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+          shareTokenProgram: token.TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: token.ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        })
+        .signers([investor])
+        .rpc();
+
+      // 10 less the 2.5% platform fee is sent; the mint withholds 1% of that
+      const principal = afterTransferFee(BigInt(9_750_000));
+      const record = await program.account.investmentAccount.fetch(positionPda);
+      assert.equal(BigInt(record.principalUsd.toString()), principal);
+      assert.equal(BigInt(record.shares.toString()), principal);
+      assert.equal(await feeBalanceOf(escrowFeeAccount), principal);
+      assert.equal(await feeBalanceOf(treasuryFeeAccount), afterTransferFee(BigInt(250_000)));
+
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.equal(BigInt(startup.totalRaised.toString()), principal);
+      await assertEscrowTotalMatchesVault();
+    });
+
+    it("Releases a tranche and debits escrow by the amount sent", async () => {
+      const milestonePda = pda(Buffer.from("milestone"), FEE_STARTUP_ID.toBuffer(), Buffer.from([0]));
+      await program.methods
+        .createMilestone(
+          FEE_STARTUP_ID,
+          Array.from(Buffer.alloc(32, 2)),
+          5_000,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 90 * 24 * 60 * 60)
+        )
+        .accounts({
+          admin: admin,
+          config: configPda,
+          startup: startupPda,
+          milestone: milestonePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      await program.methods
+        .approveMilestone()
+        .accounts({
+          releaseAuthority: admin,
+          escrowState: escrowStatePda,
+          milestone: milestonePda,
+          startup: startupPda,
+        })
+        .rpc();
+      await program.methods
+        .updateStartupStatus(FEE_STARTUP_ID, { closed: {} })
+        .accounts({ admin: admin, config: configPda, startup: startupPda })
+        .rpc();
+      await program.methods.finalizeRaise(FEE_STARTUP_ID).accounts({ startup: startupPda }).rpc();
+
+      const releaseProposalPda = pda(
+        Buffer.from("release_proposal"),
+        FEE_STARTUP_ID.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8)
+      );
+      await program.methods
+        .proposeRelease(FEE_STARTUP_ID, 0)
+        .accounts({
+          proposer: admin,
+          config: configPda,
+          startup: startupPda,
+          releaseProposal: releaseProposalPda,
+          milestone: milestonePda,
+          destinationAta: payoutFeeAccount,
+          mint: FEE_MINT,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const escrowBefore = await feeBalanceOf(escrowFeeAccount);
+      const payoutBefore = await feeBalanceOf(payoutFeeAccount);
+
+      await program.methods
+        .releaseFunds(FEE_STARTUP_ID)
+        .accounts({
+          executor: admin,
+          escrowState: escrowStatePda,
+          config: configPda,
+          startup: startupPda,
+          acceptedMint: feeAcceptedMintPda,
+          releaseProposal: releaseProposalPda,
+          milestone: milestonePda,
+          escrowAta: escrowFeeAccount,
+          destinationAta: payoutFeeAccount,
+          escrowPda: escrowPda,
+          treasuryAta: treasuryFeeAccount,
+          feePda: feePda,
+          mint: FEE_MINT,
+          tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      // Half the raise leaves escrow; the startup is credited that less the transfer fee
+      const tranche = escrowBefore / BigInt(2);
+      assert.equal(escrowBefore - (await feeBalanceOf(escrowFeeAccount)), tranche);
+      assert.equal((await feeBalanceOf(payoutFeeAccount)) - payoutBefore, afterTransferFee(tranche));
+
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.equal(BigInt(startup.totalReleased.toString()), tranche);
+      await assertEscrowTotalMatchesVault();
+    });
+
+    it("Refunds what is left in escrow when the startup is cancelled", async () => {
+      await program.methods
+        .updateStartupStatus(FEE_STARTUP_ID, { cancelled: {} })
+        .accounts({ admin: admin, config: configPda, startup: startupPda })
+        .rpc();
+
+      const escrowBefore = await feeBalanceOf(escrowFeeAccount);
+      const investorBefore = await feeBalanceOf(investorFeeAccount);
+
+      await program.methods
+        .cancelInvestment()
+        .accounts({
+          ...cancelAccounts(investor.publicKey, positionPda, FEE_STARTUP_ID),
+          acceptedMint: feeAcceptedMintPda,
+          investorTokenAccount: investorFeeAccount,
+          programEscrowAta: escrowFeeAccount,
+          mint: FEE_MINT,
+          tokenProgram: token.TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([investor])
+        .rpc();
+
+      // The sole position gets the whole remaining escrow, less the transfer fee
+      assert.equal(await feeBalanceOf(escrowFeeAccount), BigInt(0));
+      assert.equal((await feeBalanceOf(investorFeeAccount)) - investorBefore, afterTransferFee(escrowBefore));
+
+      const record = await program.account.investmentAccount.fetch(positionPda);
+      assert.deepEqual(record.status, { cancelled: {} });
+      const startup = await program.account.startup.fetch(startupPda);
+      assert.equal(BigInt(startup.totalRefunded.toString()), escrowBefore);
+      await assertEscrowTotalMatchesVault();
     });
   });
 });